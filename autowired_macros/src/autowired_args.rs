@@ -1,20 +1,32 @@
 use darling::{FromMeta, Error, ast::NestedMeta};
-use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use syn::{Ident, LitStr, Path};
 
 
-#[derive(FromMeta)]
+#[derive(FromMeta, Clone)]
 pub struct AutowiredArgs {
   #[darling(default)]
   pub clone: bool,
   #[darling(default, rename = "async_")]
   pub asyncness: bool,
-  pub ctx: Ident,
+  pub ctx: Path,
+  /// The variant an enum is constructed as.
+  pub variant: Option<Ident>,
+  /// The profile the provider is built for.
+  pub profile: Option<LitStr>,
+  /// A `fn() -> bool` deciding whether the provider is built.
+  pub when: Option<Path>,
+  /// Built once per child scope, e.g. per request, rather than with the provider.
+  #[darling(default)]
+  pub scoped: bool,
+  /// Provides a `{T}Factory` creating `T` from the function's `#[runtime]` arguments.
+  #[darling(default)]
+  pub assisted: bool,
 }
 
 impl AutowiredArgs {
   pub fn parse(args: TokenStream) -> Result<Self, Error> {
-    let args = NestedMeta::parse_meta_list(args.into())?;
+    let args = NestedMeta::parse_meta_list(args)?;
     let result = Self::from_list(&args)?;
     Ok(result)
   }
@@ -0,0 +1,108 @@
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Attribute, Error, FnArg, ItemStruct, Pat, ReturnType, Type};
+
+use crate::{
+  autowired_args::AutowiredArgs,
+  autowired_data::AutowiredData,
+  autowired_input::{is_arg_attr, AutowiredInput, Method},
+  expand,
+};
+
+/// `{T}Factory`, the provider registered by an assisted provider of `T`.
+pub fn factory_ident(ty: &Type) -> Option<Ident> {
+  match ty {
+    Type::Path(p) => p.path.segments.last().map(|s| format_ident!("{}Factory", s.ident)),
+    _ => None,
+  }
+}
+
+fn is_runtime(a: &Attribute) -> bool {
+  a.path().is_ident("runtime")
+}
+
+/// Expands an assisted provider: the function is kept as is, and a factory
+/// taking its `#[runtime]` arguments is provided instead, with the other
+/// arguments autowired into it.
+pub fn expand_assisted(args: AutowiredArgs, input: AutowiredInput) -> syn::Result<TokenStream2> {
+  // methods stay in their impl block
+  let (mut f, call, is_fn) = match input {
+    AutowiredInput::Fn(f) => {
+      let ident = &f.sig.ident;
+      (f.clone(), quote!(#ident), true)
+    }
+    AutowiredInput::Method(Method { self_ty, f, .. }) => {
+      let ident = &f.sig.ident;
+      (f.clone(), quote!(<#self_ty>::#ident), false)
+    }
+    AutowiredInput::AsyncFn(f) => return Err(Error::new_spanned(&f.sig, "assisted providers cannot be async")),
+    AutowiredInput::Struct(_) | AutowiredInput::Enum(_) => {
+      return Err(Error::new(Span::call_site(), "assisted providers must be functions"))
+    }
+  };
+
+  if f.sig.asyncness.is_some() {
+    return Err(Error::new_spanned(&f.sig, "assisted providers cannot be async"));
+  }
+  if !f.sig.generics.params.is_empty() {
+    return Err(Error::new_spanned(&f.sig.generics, "assisted providers cannot be generic"));
+  }
+  let ReturnType::Type(_, ret) = &f.sig.output else {
+    return Err(Error::new_spanned(&f.sig, "autowired functions must return the provided type"));
+  };
+  let ret = (**ret).clone();
+  let factory = factory_ident(&ret).ok_or_else(|| Error::new_spanned(&ret, "expected a type path"))?;
+
+  let mut fields = vec![];
+  let mut params = vec![];
+  let mut call_args = vec![];
+  for (i, arg) in f.sig.inputs.iter_mut().enumerate() {
+    let FnArg::Typed(arg) = arg else {
+      return Err(Error::new_spanned(arg, "assisted providers cannot take `self`"));
+    };
+    let ident = match &*arg.pat {
+      Pat::Ident(p) => p.ident.clone(),
+      _ => format_ident!("arg{}", i),
+    };
+    let ty = &arg.ty;
+
+    if arg.attrs.iter().any(is_runtime) {
+      params.push(quote!(#ident: #ty));
+      call_args.push(quote!(#ident));
+    } else {
+      // `#[inject(..)]` and `#[name = ".."]` apply to the factory's field
+      let attrs = arg.attrs.iter().filter(|a| is_arg_attr(a));
+      fields.push(quote!(#(#attrs)* #ident: #ty));
+      call_args.push(quote!(::std::clone::Clone::clone(&self.#ident)));
+    }
+    arg.attrs.retain(|a| !is_runtime(a) && !is_arg_attr(a));
+  }
+
+  let vis = &f.vis;
+  let item: ItemStruct = parse_quote! {
+    #vis struct #factory {
+      #(#fields),*
+    }
+  };
+  let provider = expand(AutowiredData::new(
+    AutowiredArgs {
+      assisted: false,
+      clone: true,
+      ..args
+    },
+    AutowiredInput::Struct(item),
+  )?)?;
+  let f = if is_fn { quote!(#f) } else { quote!() };
+
+  Ok(quote! {
+    #f
+
+    #provider
+
+    impl #factory {
+      pub fn create(&self, #(#params),*) -> #ret {
+        #call(#(#call_args),*)
+      }
+    }
+  })
+}
@@ -0,0 +1,69 @@
+use darling::{ast::NestedMeta, FromMeta};
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Error, ItemFn, ItemStruct, LitStr, Path};
+
+use crate::{autowired_args::AutowiredArgs, autowired_data::AutowiredData, autowired_input::AutowiredInput, expand};
+
+#[derive(FromMeta)]
+struct AutowiredConfigArgs {
+  ctx: Path,
+  /// Dotted path of the struct's table in the `Config`.
+  prefix: Option<LitStr>,
+}
+
+/// The provider function deserializing config struct `ident`.
+pub fn config_fn_ident(ident: &Ident) -> Ident {
+  format_ident!("{}_from_config", ident)
+}
+
+pub fn expand_autowired_config(args: TokenStream2, input: TokenStream2) -> TokenStream2 {
+  let args = match NestedMeta::parse_meta_list(args)
+    .map_err(darling::Error::from)
+    .and_then(|args| AutowiredConfigArgs::from_list(&args))
+  {
+    Ok(args) => args,
+    Err(e) => return e.write_errors(),
+  };
+
+  match expand_struct(args, input) {
+    Ok(expanded) => expanded,
+    Err(e) => e.into_compile_error(),
+  }
+}
+
+fn expand_struct(args: AutowiredConfigArgs, input: TokenStream2) -> syn::Result<TokenStream2> {
+  let s = syn::parse2::<ItemStruct>(input)?;
+  if !s.generics.params.is_empty() {
+    return Err(Error::new_spanned(&s.generics, "config structs cannot be generic"));
+  }
+
+  let ident = &s.ident;
+  let f = config_fn_ident(ident);
+  let prefix = args.prefix.map(|p| p.value()).unwrap_or_default();
+  let provider: ItemFn = parse_quote! {
+    #[allow(non_snake_case)]
+    fn #f(config: ::autowired::config::Config) -> #ident {
+      config.get(#prefix).unwrap_or_else(|e| panic!("{}", e))
+    }
+  };
+  let args = AutowiredArgs {
+    clone: false,
+    asyncness: false,
+    ctx: args.ctx,
+    variant: None,
+    profile: None,
+    when: None,
+    scoped: false,
+    assisted: false,
+  };
+  let provider = expand(AutowiredData::new(args, AutowiredInput::Fn(provider))?)?;
+
+  Ok(quote! {
+    #[derive(::autowired::serde::Deserialize)]
+    #[serde(crate = "::autowired::serde")]
+    #s
+
+    #provider
+  })
+}
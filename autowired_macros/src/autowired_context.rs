@@ -0,0 +1,316 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+  parse::{Parse, ParseStream},
+  punctuated::Punctuated,
+  token::Comma,
+  spanned::Spanned,
+  Attribute, Error, Expr, ExprLit, ItemStruct, Lit, LitStr, Member, Meta, Token, Type,
+};
+
+fn attr_list<T: Parse>(attrs: &[Attribute], name: &str) -> syn::Result<Vec<T>> {
+  match attrs.iter().find(|a| a.path().is_ident(name)) {
+    Some(a) => Ok(
+      a.parse_args_with(Punctuated::<T, Comma>::parse_terminated)?
+        .into_iter()
+        .collect(),
+    ),
+    None => Ok(vec![]),
+  }
+}
+
+/// A type of `#[conditional(..)]`, `async` if any of its providers is.
+struct Conditional {
+  asyncness: Option<Token![async]>,
+  ty: Type,
+}
+
+impl Parse for Conditional {
+  fn parse(input: ParseStream) -> syn::Result<Self> {
+    Ok(Self {
+      asyncness: input.parse()?,
+      ty: input.parse()?,
+    })
+  }
+}
+
+/// A field of a `#[derive(Context)]` struct and how it is bound.
+struct ContextField {
+  member: Member,
+  ty: Type,
+  /// `#[skip]`: not a dependency.
+  skip: bool,
+  /// `#[flatten]`: the field is a context itself, whose bindings are pulled in.
+  flatten: bool,
+  /// `#[import]`: like `#[flatten]`, also registering the context's providers.
+  import: bool,
+  /// `#[profile]`: the active profile rather than a dependency.
+  profile: bool,
+  /// `#[name = ".."]`: bound by name rather than by type.
+  name: Option<LitStr>,
+  /// `#[provide(as = ..)]`: bound as another type, e.g. a trait object.
+  provide_as: Option<Type>,
+}
+
+impl ContextField {
+  fn new(i: usize, f: &syn::Field) -> syn::Result<Self> {
+    let mut field = Self {
+      member: match &f.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(i.into()),
+      },
+      ty: f.ty.clone(),
+      skip: false,
+      flatten: false,
+      import: false,
+      profile: false,
+      name: None,
+      provide_as: None,
+    };
+
+    for a in &f.attrs {
+      if a.path().is_ident("skip") {
+        a.meta.require_path_only()?;
+        field.skip = true;
+      } else if a.path().is_ident("flatten") {
+        a.meta.require_path_only()?;
+        field.flatten = true;
+      } else if a.path().is_ident("import") {
+        a.meta.require_path_only()?;
+        field.import = true;
+      } else if a.path().is_ident("profile") {
+        a.meta.require_path_only()?;
+        field.profile = true;
+      } else if a.path().is_ident("name") {
+        match &a.meta {
+          Meta::NameValue(nv) => match &nv.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(name), .. }) => field.name = Some(name.clone()),
+            v => return Err(Error::new_spanned(v, "expected a string literal")),
+          },
+          m => return Err(Error::new_spanned(m, "expected `#[name = \"..\"]`")),
+        }
+      } else if a.path().is_ident("provide") {
+        field.provide_as = Some(a.parse_args_with(|input: ParseStream| {
+          input.parse::<Token![as]>()?;
+          input.parse::<Token![=]>()?;
+          input.parse::<Type>()
+        })?);
+      }
+    }
+
+    let kinds = [field.skip, field.flatten, field.import, field.profile];
+    if kinds.contains(&true) && (field.name.is_some() || field.provide_as.is_some()) {
+      return Err(Error::new_spanned(
+        f,
+        "`#[skip]`, `#[flatten]`, `#[import]` and `#[profile]` fields cannot be named or provided as another type",
+      ));
+    }
+    if kinds.iter().filter(|b| **b).count() > 1 {
+      return Err(Error::new_spanned(
+        f,
+        "`#[skip]`, `#[flatten]`, `#[import]` and `#[profile]` are mutually exclusive",
+      ));
+    }
+
+    Ok(field)
+  }
+
+  /// The type the field is bound as.
+  fn binding(&self) -> TokenStream2 {
+    match &self.provide_as {
+      Some(t @ Type::TraitObject(_)) => quote!(::std::sync::Arc<#t>),
+      Some(t) => quote!(#t),
+      None => self.ty.to_token_stream(),
+    }
+  }
+
+  fn value(&self) -> TokenStream2 {
+    let member = &self.member;
+    let binding = self.binding();
+    match &self.provide_as {
+      Some(Type::TraitObject(_)) => quote! {{
+        let v: #binding = ::std::sync::Arc::new(self.#member.clone());
+        v
+      }},
+      Some(_) => quote! {{
+        let v: #binding = self.#member.clone();
+        v
+      }},
+      None => quote!(self.#member.clone()),
+    }
+  }
+}
+
+pub struct AutowiredContext {
+  ident: Ident,
+  modules: Vec<Expr>,
+  instantiate: Vec<Type>,
+  /// Types provided by conditional providers, see `#[autowired(profile = ..)]`;
+  /// `async T` if any provider of `T` is async.
+  conditional: Vec<Conditional>,
+  /// Types bound per child scope, see `Provider::child`.
+  scoped: Vec<Type>,
+  /// Types bound at runtime, see `ProviderBuilder::bind`.
+  bound: Vec<Type>,
+  /// Entry points, from which every used provider is reachable.
+  roots: Vec<Type>,
+  /// `#[skip_unused]`: providers unreachable from the roots are not built.
+  skip_unused: bool,
+  fields: Vec<ContextField>,
+}
+
+impl Parse for AutowiredContext {
+  fn parse(input: ParseStream) -> Result<Self, Error> {
+    let ItemStruct { attrs, ident, fields, .. } = input.parse()?;
+
+    Ok(Self {
+      ident,
+      modules: attr_list(&attrs, "modules")?,
+      instantiate: attr_list(&attrs, "instantiate")?,
+      conditional: attr_list(&attrs, "conditional")?,
+      scoped: attr_list(&attrs, "scoped")?,
+      bound: attr_list(&attrs, "bound")?,
+      roots: attr_list(&attrs, "roots")?,
+      skip_unused: match attrs.iter().find(|a| a.path().is_ident("skip_unused")) {
+        Some(a) => {
+          a.meta.require_path_only()?;
+          true
+        }
+        None => false,
+      },
+      fields: fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| ContextField::new(i, f))
+        .collect::<syn::Result<_>>()?,
+    })
+  }
+}
+
+impl ToTokens for AutowiredContext {
+  fn to_tokens(&self, tokens: &mut TokenStream2) {
+    let Self { ident, modules, instantiate, conditional, scoped, bound, roots, skip_unused, .. } = self;
+    let fields = self.fields.iter().filter(|f| !f.skip && !f.profile);
+    let imports = self.fields.iter().filter(|f| f.import).map(|f| &f.member).collect::<Vec<_>>();
+    let nested = self.fields.iter().filter(|f| f.flatten || f.import).map(|f| &f.member);
+
+    let (inserts, impls): (Vec<_>, Vec<_>) = fields
+      .map(|f| {
+        let member = &f.member;
+        let ty = &f.ty;
+        let binding = f.binding();
+        let value = f.value();
+
+        if f.flatten || f.import {
+          (
+            quote! { deps.0.extend(::autowired::Context::get_initial_deps(&self.#member).0); },
+            quote! {
+              impl<T, P> ::autowired::Provides<T, (#ty, P)> for #ident
+              where
+                #ty: ::autowired::Provides<T, P>
+              {}
+            },
+          )
+        } else if let Some(name) = &f.name {
+          (quote! { deps.insert_named::<#binding>(#name, #value); }, quote!())
+        } else {
+          (
+            quote! {
+              deps.0.insert(
+                ::autowired::TypeId::of::<#binding>(),
+                ::std::sync::Arc::new(#value) as ::autowired::DependencyValue,
+              );
+            },
+            quote! {
+              impl ::autowired::Provides<#binding> for #ident {}
+              impl ::autowired::SharedDep<#ident> for #binding {}
+            },
+          )
+        }
+      })
+      .unzip();
+
+    let profile = match self.fields.iter().find(|f| f.profile) {
+      Some(f) => {
+        let member = &f.member;
+        quote! {
+          fn profile(&self) -> Option<&str> {
+            Some(::std::convert::AsRef::<str>::as_ref(&self.#member))
+          }
+        }
+      }
+      None => quote!(),
+    };
+
+    let conditional = conditional.iter().map(|Conditional { asyncness, ty }| {
+      let impl_async = asyncness.map(|_| quote!(impl ::autowired::AsyncAutowiredDep for #ty {}));
+      quote! {
+        impl ::autowired::Provides<#ty> for #ident {}
+        impl ::autowired::AutowiredDep for #ty {}
+        #impl_async
+      }
+    });
+
+    let root_checks = roots.iter().map(|r| {
+      quote_spanned! {r.span()=>
+        const _: () = {
+          let _ = ::autowired::provided::<#ident, #r, _>;
+        };
+      }
+    });
+
+    quote! {
+      #[::autowired::async_trait]
+      impl ::autowired::Context for #ident {
+        fn get_initial_deps(&self) -> ::autowired::Deps {
+          #[allow(unused_mut)]
+          let mut deps = ::autowired::Deps::default();
+          #(#inserts)*
+          deps
+        }
+
+        fn modules(&self) -> Vec<&'static ::autowired::Module> {
+          #[allow(unused_mut)]
+          let mut modules: Vec<&'static ::autowired::Module> = vec![#(&#modules),*];
+          #(modules.extend(::autowired::Context::modules(&self.#imports));)*
+          modules
+        }
+
+        fn instantiations(&self) -> Vec<::autowired::Registration> {
+          #[allow(unused_mut)]
+          let mut instantiations = vec![#(<#instantiate as ::autowired::GenericDep>::REGISTRATION),*];
+          #(instantiations.extend(::autowired::Context::instantiations(&self.#imports));)*
+          instantiations
+        }
+
+        #profile
+
+        fn bound_types(&self) -> Vec<::autowired::TypeId> {
+          #[allow(unused_mut)]
+          let mut types = vec![#(::autowired::TypeId::of::<#scoped>(),)* #(::autowired::TypeId::of::<#bound>()),*];
+          #(types.extend(::autowired::Context::bound_types(&self.#nested));)*
+          types
+        }
+
+        fn roots(&self) -> Vec<::autowired::TypeId> {
+          #[allow(unused_mut)]
+          let mut roots = vec![#(::autowired::TypeId::of::<#roots>()),*];
+          #(roots.extend(::autowired::Context::roots(&self.#imports));)*
+          roots
+        }
+
+        fn skip_unused(&self) -> bool {
+          #skip_unused
+        }
+      }
+
+      #(#root_checks)*
+
+      #(#impls)*
+      #(#conditional)*
+      #(impl ::autowired::Provides<#scoped> for #ident {})*
+      #(impl ::autowired::Provides<#bound> for #ident {})*
+    }
+    .to_tokens(tokens)
+  }
+}
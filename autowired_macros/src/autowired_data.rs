@@ -1,10 +1,10 @@
-use std::{collections::HashMap, future::IntoFuture};
+use std::collections::HashMap;
 
-use crate::{autowired_input::AutowiredInput, autowired_args::AutowiredArgs};
-use darling::FromField;
-use quote::{quote, format_ident, ToTokens};
-use proc_macro2::TokenStream as TokenStream2;
-use syn::{ItemStruct, ItemFn, Signature, ReturnType, Type, FnArg, FieldsNamed, Fields, FieldsUnnamed, Expr, Meta, parse::Parse, Attribute};
+use crate::{autowired_input::{AutowiredInput, Method}, autowired_args::AutowiredArgs};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{parse_quote, spanned::Spanned, Generics, ItemEnum, Variant, WhereClause};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use syn::{ItemStruct, ItemFn, Signature, ReturnType, Type, FnArg, Fields, Expr, Meta, Attribute};
 
 pub struct AutowiredData {
   pub args: AutowiredArgs,
@@ -12,28 +12,89 @@ pub struct AutowiredData {
   pub inject: HashMap<usize, Expr>,
 }
 
-fn deps_get() -> TokenStream2 {
-  quote! { deps.get() }
+fn type_name(t: &impl ToTokens) -> String {
+  t.to_token_stream().to_string().split_whitespace().collect()
 }
 
-fn detach_attrs(i: usize, attrs: &mut Vec<Attribute>) -> HashMap<usize, Expr> {
+fn respan(t: &impl ToTokens, span: Span) -> TokenStream2 {
+  t.to_token_stream()
+    .into_iter()
+    .map(|mut t| {
+      t.set_span(span);
+      t
+    })
+    .collect()
+}
+
+/// How a dependency of type `Wrapper<T>` is resolved from the provided `T`.
+enum Wrapper {
+  /// `Arc<T>` shares `T` instead of cloning it.
+  Shared,
+  /// `Lazy<T>` resolves `T` once the provider is built.
+  Lazy,
+  /// `Factory<T>` constructs a new `T` on each call.
+  Factory,
+}
+
+/// The wrapper and `T`, if `ty` is `Arc<T>`, `Lazy<T>` or `Factory<T>`.
+/// `Arc<dyn Trait>` is never provided as `dyn Trait`, so it is bound as is.
+fn wrapped_type(ty: &Type) -> Option<(Wrapper, &Type)> {
+  let Type::Path(p) = ty else {
+    return None;
+  };
+  let last = p.path.segments.last()?;
+  let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+    return None;
+  };
+  let (Some(syn::GenericArgument::Type(t)), 1) = (args.args.first(), args.args.len()) else {
+    return None;
+  };
+  match last.ident.to_string().as_str() {
+    "Arc" if !matches!(t, Type::TraitObject(_)) => Some((Wrapper::Shared, t)),
+    "Lazy" => Some((Wrapper::Lazy, t)),
+    "Factory" => Some((Wrapper::Factory, t)),
+    _ => None,
+  }
+}
+
+fn deps_get(ty: &Type) -> TokenStream2 {
+  match wrapped_type(ty) {
+    Some((Wrapper::Shared, _)) => quote! { deps.get_arc() },
+    Some((Wrapper::Lazy, _)) => quote! { deps.lazy() },
+    Some((Wrapper::Factory, _)) => quote! { deps.factory() },
+    None => quote! { deps.get() },
+  }
+}
+
+/// Removes the `#[inject(..)]` and `#[name = ".."]` attributes of field `i`, returning
+/// the expression it is initialized with. With `into`, the injected expression is
+/// converted into the field's type, e.g. a `&str` into a `String`.
+fn detach_attrs(i: usize, attrs: &mut Vec<Attribute>, into: bool) -> HashMap<usize, Expr> {
   let mut exprs = HashMap::new();
   
   attrs.retain(|a| {
+    // `#[name = ".."]` resolves a named context binding
+    if let Meta::NameValue(a) = &a.meta {
+      if a.path.is_ident("name") {
+        let name = &a.value;
+        exprs.insert(i, parse_quote!(deps.get_named(#name)));
+        return false
+      }
+    }
 
     let Meta::List(a) = &a.meta else {
       return true
     };
 
-    if !a.path.segments.last().is_some_and(|a| a.ident == "inject") {
+    if a.path.segments.last().is_none_or(|a| a.ident != "inject") {
       return true
     };
 
-    let Some(e) = syn::parse::<Expr>(a.tokens.clone().into()).ok() else {
+    let Some(e) = syn::parse2::<Expr>(a.tokens.clone()).ok() else {
       return true
     };
 
-    exprs.insert(i, e);
+    exprs.insert(i, if into { parse_quote!(::std::convert::Into::into(#e)) } else { e });
 
     false
   });
@@ -41,33 +102,142 @@ fn detach_attrs(i: usize, attrs: &mut Vec<Attribute>) -> HashMap<usize, Expr> {
   exprs
 }
 
+fn input_generics(input: &AutowiredInput) -> &Generics {
+  match input {
+    AutowiredInput::Struct(s) => &s.generics,
+    AutowiredInput::Enum(e) => &e.generics,
+    AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) | AutowiredInput::Method(Method { f, .. }) => &f.sig.generics,
+  }
+}
+
 impl AutowiredData {
-  pub fn new(args: AutowiredArgs, mut input: AutowiredInput) -> Self {
+  pub fn new(args: AutowiredArgs, mut input: AutowiredInput) -> Result<Self, syn::Error> {
     let mut inject = HashMap::new();
     
     match &mut input {
       AutowiredInput::Struct(s) => {
+        // `#[inject(..)]` on a newtype injects its only field
+        if let Fields::Unnamed(fields) = &s.fields {
+          if fields.unnamed.len() == 1 {
+            inject.extend(detach_attrs(0, &mut s.attrs, true))
+          }
+        }
         for (i, f) in s.fields.iter_mut().enumerate() {
-          inject.extend(detach_attrs(i, &mut f.attrs))
+          inject.extend(detach_attrs(i, &mut f.attrs, false))
         }
       },
-      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) => {
+      AutowiredInput::Enum(e) => {
+        let Some(variant) = &args.variant else {
+          return Err(syn::Error::new_spanned(&e.ident, "autowired enums require a `variant = ..` argument"));
+        };
+        let Some(v) = e.variants.iter_mut().find(|v| v.ident == *variant) else {
+          return Err(syn::Error::new_spanned(variant, format!("no variant `{}` in `{}`", variant, e.ident)));
+        };
+        for (i, f) in v.fields.iter_mut().enumerate() {
+          inject.extend(detach_attrs(i, &mut f.attrs, false))
+        }
+      },
+      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) | AutowiredInput::Method(Method { f, .. }) => {
         for (i, f) in f.sig.inputs.iter_mut().enumerate().filter_map(|(i, a)| if let FnArg::Typed(a) = a { Some((i, a)) } else { None }) {
-          inject.extend(detach_attrs(i, &mut f.attrs))
+          inject.extend(detach_attrs(i, &mut f.attrs, false))
         }
       },
     }
 
-    println!("{:?}", inject);
+    if (args.profile.is_some() || args.when.is_some()) && !input_generics(&input).params.is_empty() {
+      return Err(syn::Error::new_spanned(input_generics(&input), "generic providers cannot be conditional"));
+    }
 
-    Self {
+    Ok(Self {
       args, input, inject
+    })
+  }
+
+  /// Conditional providers are candidates: several may provide the same type,
+  /// so the type's bindings are declared by the context, see `#[conditional(..)]`.
+  pub fn is_conditional(&self) -> bool {
+    self.args.profile.is_some() || self.args.when.is_some()
+  }
+
+  pub fn condition(&self) -> TokenStream2 {
+    let profile = match &self.args.profile {
+      Some(p) => quote!(Some(#p)),
+      None => quote!(None),
+    };
+    let when = match &self.args.when {
+      Some(w) => quote!(Some(#w)),
+      None => quote!(None),
+    };
+    quote! { ::autowired::Condition { profile: #profile, when: #when } }
+  }
+
+  /// The variant an autowired enum is constructed as.
+  pub fn variant(&self) -> Option<&Variant> {
+    match (&self.input, &self.args.variant) {
+      (AutowiredInput::Enum(e), Some(variant)) => e.variants.iter().find(|v| v.ident == *variant),
+      _ => None,
     }
   }
 
-  pub fn typename(&self) -> Result<TokenStream2, String> {
+  /// Names the generated items, which live next to the annotated one: its
+  /// ident is unique within the module already, and methods are prefixed
+  /// with their type's. Spans are left out, so that moving the item around
+  /// does not change the expansion.
+  pub fn ident(&self) -> Ident {
     match &self.input {
-      AutowiredInput::Struct(ItemStruct { ident, .. }) => Ok(quote! { #ident }),
+      AutowiredInput::Struct(s) => s.ident.clone(),
+      AutowiredInput::Enum(e) => e.ident.clone(),
+      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) => f.sig.ident.clone(),
+      AutowiredInput::Method(m) => m.ident(),
+    }
+  }
+
+  pub fn generics(&self) -> &Generics {
+    input_generics(&self.input)
+  }
+
+  /// Generic providers are registered per instantiation, see `::autowired::GenericDep`.
+  pub fn is_generic(&self) -> bool {
+    !self.generics().params.is_empty()
+  }
+
+  /// `::<T, ..>` to name the item's or the generated functions' instantiations.
+  pub fn turbofish(&self) -> TokenStream2 {
+    let (_, ty_generics, _) = self.generics().split_for_impl();
+    ty_generics.as_turbofish().into_token_stream()
+  }
+
+  /// The item's where clause, extended with the bounds every provided type must meet.
+  pub fn where_clause(&self) -> syn::Result<WhereClause> {
+    let type_ = self.typename()?;
+    let mut where_clause = self
+      .generics()
+      .where_clause
+      .clone()
+      .unwrap_or_else(|| parse_quote!(where));
+
+    // spanned at the call site, so that clippy does not report the item's
+    // inline bounds as defined in more than one place
+    for t in self.generics().type_params() {
+      let t = respan(&t.ident, Span::call_site());
+      where_clause.predicates.push(parse_quote!(#t: 'static));
+    }
+    if self.is_generic() {
+      where_clause
+        .predicates
+        .push(parse_quote!(#type_: Send + Sync + 'static));
+    }
+
+    Ok(where_clause)
+  }
+
+  pub fn typename(&self) -> syn::Result<TokenStream2> {
+    match &self.input {
+      AutowiredInput::Struct(ItemStruct { ident, generics, .. })
+      | AutowiredInput::Enum(ItemEnum { ident, generics, .. }) => {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        Ok(quote! { #ident #ty_generics })
+      }
       AutowiredInput::Fn(ItemFn {
         sig: Signature {
           output: ReturnType::Type(_, t),
@@ -82,15 +252,32 @@ impl AutowiredData {
         },
         ..
       }) => Ok(quote! { #t }),
-      AutowiredInput::Fn(_) => Err("typename error".into()),
-      AutowiredInput::AsyncFn(_) => Err("typename error".into()),
+      AutowiredInput::Method(Method {
+        f: ItemFn {
+          sig: Signature {
+            output: ReturnType::Type(_, t),
+            ..
+          },
+          ..
+        },
+        ..
+      }) => Ok(quote! { #t }),
+      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) | AutowiredInput::Method(Method { f, .. }) => Err(
+        syn::Error::new_spanned(&f.sig, "autowired functions must return the provided type"),
+      ),
     }
   }
 
   pub fn children(&self) -> Vec<Type> {
     match &self.input {
       AutowiredInput::Struct(s) => s.fields.iter().map(|f| &f.ty).cloned().collect(),
-      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) => f
+      AutowiredInput::Enum(_) => self
+        .variant()
+        .into_iter()
+        .flat_map(|v| v.fields.iter().map(|f| &f.ty))
+        .cloned()
+        .collect(),
+      AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) | AutowiredInput::Method(Method { f, .. }) => f
         .sig
         .inputs
         .iter()
@@ -107,46 +294,147 @@ impl AutowiredData {
     }
   }
 
-  pub fn children_names(&self) -> Vec<String> {
+  /// Children resolved from `Deps`, i.e. without the injected ones.
+  pub fn dependencies(&self) -> Vec<Type> {
     self
       .children()
       .into_iter()
-      .map(|c| quote! { #c }.to_string())
+      .enumerate()
+      .filter(|(i, _)| !self.inject.contains_key(i))
+      .map(|(_, c)| c)
       .collect()
   }
 
+  /// The types dependencies are provided as, i.e. `T` for `Arc<T>`, `Lazy<T>` and `Factory<T>`.
+  pub fn dependency_targets(&self) -> Vec<Type> {
+    self
+      .dependencies()
+      .iter()
+      .map(|c| wrapped_type(c).map_or(c, |(_, t)| t).clone())
+      .collect()
+  }
+
+  /// Targets built before this provider; `Lazy<T>` and `Factory<T>` are
+  /// resolved later, so they may even depend on it.
+  pub fn dependency_edges(&self) -> Vec<Type> {
+    self
+      .dependencies()
+      .iter()
+      .filter_map(|c| match wrapped_type(c) {
+        Some((Wrapper::Lazy | Wrapper::Factory, _)) => None,
+        Some((Wrapper::Shared, t)) => Some(t.clone()),
+        None => Some(c.clone()),
+      })
+      .collect()
+  }
+
+  /// Targets of `Lazy<T>` and `Factory<T>` dependencies.
+  pub fn dependency_deferred(&self) -> Vec<Type> {
+    self
+      .dependencies()
+      .iter()
+      .filter_map(|c| match wrapped_type(c) {
+        Some((Wrapper::Lazy | Wrapper::Factory, t)) => Some(t.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  pub fn dependency_names(&self) -> Vec<String> {
+    self.dependency_edges().iter().map(type_name).collect()
+  }
+
+  /// Targets of `Factory<T>` dependencies.
+  pub fn dependency_factories(&self) -> Vec<Type> {
+    self
+      .dependencies()
+      .iter()
+      .filter_map(|c| match wrapped_type(c) {
+        Some((Wrapper::Factory, t)) => Some(t.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  pub fn deferred_names(&self) -> Vec<String> {
+    self.dependency_deferred().iter().map(type_name).collect()
+  }
+
+  /// Targets of `Arc<T>` and `Lazy<T>` dependencies, which are resolved with
+  /// `Deps::get_arc` and so may be bound as `Arc<T>` too.
+  pub fn dependency_shared(&self) -> Vec<Type> {
+    self
+      .dependencies()
+      .iter()
+      .filter_map(|c| match wrapped_type(c) {
+        Some((Wrapper::Shared | Wrapper::Lazy, t)) => Some(t.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn construct(&self, path: TokenStream2, fields: &Fields) -> TokenStream2 {
+    match fields {
+      Fields::Named(fields) => {
+        let names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
+        let fields = fields.named.iter().enumerate().map(|(i, f)| {
+          self.inject.get(&i).map(|e| quote!{#e}).unwrap_or(deps_get(&f.ty))
+        });
+        quote! { #path { #(#names: #fields),* } }
+      },
+      Fields::Unnamed(fields) => {
+        let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
+          self.inject.get(&i).map(|e| quote!{#e}).unwrap_or(deps_get(&f.ty))
+        });
+        quote! { #path(#(#fields),*) }
+      },
+      Fields::Unit => quote! { #path }
+    }
+  }
+
+  fn args_body<'a>(&'a self, f: &'a ItemFn) -> impl Iterator<Item = TokenStream2> + 'a {
+    f.sig.inputs.iter().enumerate().map(|(i, a)| {
+      let get = match a {
+        FnArg::Typed(a) => deps_get(&a.ty),
+        FnArg::Receiver(_) => deps_get(&parse_quote!(Self)),
+      };
+      self.inject.get(&i).map(|e| quote!{#e}).unwrap_or(get)
+    })
+  }
+
   pub fn initializer_body(&self) -> TokenStream2 {
+    let turbofish = self.turbofish();
     let body = match &self.input {
       AutowiredInput::Struct(s) => {
         let ident = &s.ident;
-        match &s.fields {
-          Fields::Named(fields) => {
-            let names = fields.named.iter().map(|f| f.ident.as_ref().unwrap());
-            let fields = fields.named.iter().enumerate().map(|(i, _)| {
-              self.inject.get(&i).map(|e| quote!{#e}).unwrap_or(deps_get())
-            });
-            quote! { Box::new(#ident { #(#names: #fields),* }) }
-          },
-          Fields::Unnamed(fields) => {
-            let fields = fields.unnamed.iter().enumerate().map(|(i, _)| {
-              self.inject.get(&i).map(|e| quote!{#e}).unwrap_or(deps_get())
-            });
-            quote! { Box::new(#ident(#(#fields),*)) }
-          },
-          Fields::Unit => quote! { Box::new(#ident) }
-        }
+        self.construct(quote! { #ident #turbofish }, &s.fields)
+      }
+      AutowiredInput::Enum(e) => {
+        let ident = &e.ident;
+        let variant = self.variant().expect("variant is checked in AutowiredData::new");
+        let variant_ident = &variant.ident;
+        self.construct(quote! { #ident #turbofish::#variant_ident }, &variant.fields)
       }
       AutowiredInput::Fn(f) | AutowiredInput::AsyncFn(f) => {
         let ident = &f.sig.ident;
-        let args = f.sig.inputs.iter().map(|_| deps_get());
-        quote! { #ident(#(#args),*) }
+        let args = self.args_body(f);
+        quote! { #ident #turbofish(#(#args),*) }
+      }
+      AutowiredInput::Method(Method { self_ty, f, generics }) => {
+        let ident = &f.sig.ident;
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
+        let args = self.args_body(f);
+        quote! { <#self_ty>::#ident #turbofish(#(#args),*) }
       }
     };
 
     if self.is_async() {
-      let body = match self.input {
-        AutowiredInput::Struct(_) | AutowiredInput::Fn(_) => quote!{ async { #body } },
+      let body = match &self.input {
+        AutowiredInput::Struct(_) | AutowiredInput::Enum(_) | AutowiredInput::Fn(_) => quote!{ async { #body } },
         AutowiredInput::AsyncFn(_) => body,
+        AutowiredInput::Method(Method { f, .. }) if f.sig.asyncness.is_some() => body,
+        AutowiredInput::Method(_) => quote!{ async { #body } },
       };
       quote!{
         async fn _init<
@@ -154,14 +442,14 @@ impl AutowiredData {
           R: Send + Sync + 'static, 
           F: ::autowired::Future<Output = R>,
         >(f: F) -> ::autowired::DependencyValue {
-          Box::new(f.await)
+          ::std::sync::Arc::new(f.await)
         }
 
         Box::pin(_init(#body))
       }
     } else {
       quote!{
-        Box::new(#body)
+        ::std::sync::Arc::new(#body)
       }
     }
   }
@@ -175,6 +463,14 @@ impl AutowiredData {
     }
   }
 
+  pub fn registration_variant(&self) -> TokenStream2 {
+    if self.is_async() {
+      quote! { ::autowired::Registration::Async }
+    } else {
+      quote! { ::autowired::Registration::Sync }
+    }
+  }
+
   pub fn dep_data_type(&self) -> TokenStream2 {
     if self.is_async() {
       quote! { ::autowired::ADepData }
@@ -183,84 +479,88 @@ impl AutowiredData {
     }
   }
 
-  pub fn typecheck_children(&self) -> Result<TokenStream2, String> {
+  /// Compile-time checks that every dependency can be autowired, each
+  /// reported on the type of the offending field or argument.
+  pub fn typecheck_children(&self) -> syn::Result<TokenStream2> {
     let ctx = &self.args.ctx;
 
-    let children = self.children().into_iter().enumerate().filter(|(i, _)| !self.inject.contains_key(i)).map(|(_, e)| e).collect::<Vec<_>>();
-    let children_idents = 
-      children
-      .iter()
-      .enumerate()
-      .map(|(i, _)| format_ident!("MSG_{}", i))
-      .collect::<Vec<_>>();
-    let children_msgs = children.iter().map(|t| quote!(#t).to_string().split_whitespace().collect::<String>() + ", ");
-
-    let type_ = &self.typename()?;
-    let name = uuid::Uuid::new_v4().as_simple().to_string();
-    //let name = type_.to_string();
-
-    let check_ident = format_ident!("__AUTOWIRED_{}_check", name);
-    let r_ident = format_ident!("__AUTOWIRED_{}_R", name);
-    let check_async_ident = format_ident!("__AUTOWIRED_{}_check_async", name);
-    let r_async_ident = format_ident!("__AUTOWIRED_{}_R_async", name);
-
-    if children.is_empty() {
+    // generic providers carry the check as bounds on their impls instead
+    if self.is_generic() {
       return Ok(quote!());
     }
 
-    let result = quote! {
-      #[allow(non_upper_case_globals)]
-      const fn #check_async_ident() -> (bool, &'static str) {
-        // const CHILDREN_ASYNC: bool = #(::autowired::impls!(#children: ADep<#ctx>))||*;
-        const CHILDREN_ASYNC: bool = #(::autowired::impls!(#children: ::autowired::AsyncAutowiredDep))||*;
+    let type_ = type_name(&self.typename()?);
 
-        // match (CHILDREN_ASYNC, ::autowired::impls!(#type_: ::autowired::AsyncAutowiredDep<#ctx>)) {
-        match (CHILDREN_ASYNC, ::autowired::impls!(#type_: ::autowired::AsyncAutowiredDep)) {
-          (true, false) => (false, "Asyncness error"),
-          _ => (true, "")
-        }
-      }
-
-      #[allow(non_upper_case_globals)]
-      const #r_async_ident: (bool, &'static str) = #check_async_ident();
-      const _: () = assert!(#r_async_ident.0, "{}", #r_async_ident.1);
-
-      #[allow(non_upper_case_globals)]
-      const fn #check_ident() -> (bool, &'static str) {
-        #(
-        // const #children_idents: &'static str = if ::autowired::impls!(#children: Dep<#ctx>) { "" } else { #children_msgs };
-        const #children_idents: &'static str = if ::autowired::impls!(#children: ::autowired::Dep<#ctx>) { "" } else { #children_msgs };
-        )*
-
-        const MSG: &'static str = ::autowired::concatcp!(#(#children_idents),*);
-        /*
-        const MSG: &'static str = match str_get!(MSG_, ..MSG_.len() - 2) {
-          Some(s) => s,
-          None => MSG_,
+    let checks = self.dependencies().into_iter().zip(self.dependency_targets()).map(|(c, target)| {
+      let name = type_name(&target);
+      // spanned on `c`, so that the error is reported on the dependency rather than on `ctx`
+      let ctx = respan(ctx, c.span());
+      let dep_check = quote_spanned! {c.span()=>
+        const _: () = {
+          let _ = ::autowired::provided::<#ctx, #c, _>;
         };
-         */
+      };
+
+      // a factory builds its target synchronously, whenever the provider is
+      let dep_check = match wrapped_type(&c) {
+        Some((Wrapper::Factory, _)) => {
+          let msg = format!("`{}` is initialized asynchronously, so `Factory<{}>` cannot create it", name, name);
+          quote_spanned! {c.span()=>
+            #dep_check
+            const _: () = assert!(!::autowired::impls!(#target: ::autowired::AsyncAutowiredDep), #msg);
+          }
+        }
+        _ => dep_check,
+      };
 
-        (MSG.is_empty(), ::autowired::formatcp!("Types [{}] cannot be autowired", MSG))
+      if self.is_async() {
+        return dep_check;
       }
 
-      #[allow(non_upper_case_globals)]
-      const #r_ident: (bool, &'static str) = #check_ident();
-      const _: () = assert!(#r_ident.0, "{}", #r_ident.1);
-    };
+      let async_msg = format!("`{}` is initialized asynchronously, so `{}` must be async too", name, type_);
+      quote_spanned! {c.span()=>
+        #dep_check
+        const _: () = assert!(!::autowired::impls!(#target: ::autowired::AsyncAutowiredDep), #async_msg);
+      }
+    });
 
-    Ok(result)
+    Ok(quote! { #(#checks)* })
   }
 
-  pub fn impl_autowired(&self) -> Result<TokenStream2, String> {
+  pub fn impl_autowired(&self) -> syn::Result<TokenStream2> {
+    // several candidates would implement the traits below for one type, so
+    // the context declares the provided type instead, see `#[conditional(..)]`
+    if self.is_conditional() {
+      if !self.is_async() || self.is_generic() {
+        return Ok(quote!());
+      }
+      let type_ = self.typename()?;
+      let name = type_name(&type_);
+      let msg = format!(
+        "`{}` is initialized asynchronously, so the context must declare it with `#[conditional(async {})]`",
+        name, name
+      );
+      return Ok(quote! {
+        const _: () = assert!(::autowired::impls!(#type_: ::autowired::AsyncAutowiredDep), #msg);
+      });
+    }
+
     let ctx = &self.args.ctx;
     let type_ = self.typename()?;
+    let (impl_generics, _, _) = self.generics().split_for_impl();
+    let mut where_clause = self.where_clause()?;
+    if self.is_generic() {
+      for c in self.dependency_targets() {
+        where_clause.predicates.push(parse_quote!(#c: ::autowired::Dep<#ctx>));
+      }
+    }
 
     // let impl_dep = quote!{ impl ::autowired::Dep<#ctx> for #type_ {} };
     // let impl_sync = quote! { impl ::autowired::AutowiredDep<#ctx> for #type_ {} };
     // let impl_async = quote! { impl ::autowired::AsyncAutowiredDep<#ctx> for #type_ {} };
-    let impl_dep = quote!{ impl ::autowired::Dep<#ctx> for #type_ {} };
-    let impl_sync = quote! { impl ::autowired::AutowiredDep for #type_ {} };
-    let impl_async = quote! { impl ::autowired::AsyncAutowiredDep for #type_ {} };
+    let impl_dep = quote!{ impl #impl_generics ::autowired::Provides<#type_> for #ctx #where_clause {} };
+    let impl_sync = quote! { impl #impl_generics ::autowired::AutowiredDep for #type_ #where_clause {} };
+    let impl_async = quote! { impl #impl_generics ::autowired::AsyncAutowiredDep for #type_ #where_clause {} };
 
     let result = if self.is_async() {
       quote! {
@@ -280,9 +580,10 @@ impl AutowiredData {
 
   pub fn is_async(&self) -> bool {
     match (&self.args, &self.input) {
-      (AutowiredArgs { asyncness, .. }, AutowiredInput::Struct(_)) => *asyncness,
+      (AutowiredArgs { asyncness, .. }, AutowiredInput::Struct(_) | AutowiredInput::Enum(_)) => *asyncness,
       (AutowiredArgs { .. }, AutowiredInput::Fn(_)) => false,
       (AutowiredArgs { .. }, AutowiredInput::AsyncFn(_)) => true,
+      (AutowiredArgs { .. }, AutowiredInput::Method(m)) => m.f.sig.asyncness.is_some(),
     }
   }
 }
@@ -291,8 +592,11 @@ impl ToTokens for AutowiredData {
   fn to_tokens(&self, tokens: &mut TokenStream2) {
     match &self.input {
       AutowiredInput::Struct(s) => s.to_tokens(tokens),
+      AutowiredInput::Enum(e) => e.to_tokens(tokens),
       AutowiredInput::Fn(f) => f.to_tokens(tokens),
       AutowiredInput::AsyncFn(f) => f.to_tokens(tokens),
+      // stays in its impl block
+      AutowiredInput::Method(_) => {}
     }
   }
 }
\ No newline at end of file
@@ -1,9 +1,17 @@
-use syn::{Error, ItemStruct, ItemFn, parse::{Parse, ParseStream}, Attribute, Visibility, Token};
+use std::collections::HashMap;
 
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, ToTokens};
+use syn::{Error, ItemStruct, ItemEnum, ItemFn, ItemImpl, ImplItem, ImplItemFn, Generics, FnArg, ReturnType, Type, parse::{Parse, ParseStream}, visit_mut::{self, VisitMut}, Attribute, Visibility, Token};
+
+// parsed once per macro invocation, so boxing the large variants would not pay off
+#[allow(clippy::large_enum_variant)]
 pub enum AutowiredInput {
   Struct(ItemStruct),
+  Enum(ItemEnum),
   Fn(ItemFn),
   AsyncFn(ItemFn),
+  Method(Method),
 }
 
 impl Parse for AutowiredInput {
@@ -19,6 +27,12 @@ impl Parse for AutowiredInput {
         vis,
         ..input.parse()?
       })
+    } else if lh.peek(Token![enum]) {
+      Self::Enum(ItemEnum {
+        attrs,
+        vis,
+        ..input.parse()?
+      })
     } else if lh.peek(Token![fn]) {
       Self::Fn(ItemFn {
         attrs,
@@ -32,9 +46,164 @@ impl Parse for AutowiredInput {
         ..input.parse()?
       })
     } else {
-      return Err(lh.error());
+      return Err(Error::new(input.span(), "#[autowired] expects a struct, an enum, a function or an impl block"));
     };
 
+    // the generated items cannot live in an impl block, so the block must be annotated instead
+    if let Self::Fn(f) | Self::AsyncFn(f) = &result {
+      if f.sig.receiver().is_some() || mentions_self(f.sig.to_token_stream()) {
+        return Err(Error::new_spanned(
+          &f.sig,
+          "#[autowired] cannot register an associated function on its own, annotate its impl block instead",
+        ));
+      }
+    }
+
     Ok(result)
   }
 }
+
+fn mentions_self(tokens: TokenStream2) -> bool {
+  tokens.into_iter().any(|t| match t {
+    TokenTree::Ident(i) => i == "Self",
+    TokenTree::Group(g) => mentions_self(g.stream()),
+    _ => false,
+  })
+}
+
+/// An associated function of an `#[autowired]` impl block.
+pub struct Method {
+  pub self_ty: Type,
+  /// The function with the impl's generics merged in and `Self` replaced by `self_ty`.
+  pub f: ItemFn,
+  /// The function's own generics, to call it with.
+  pub generics: Generics,
+}
+
+struct ReplaceSelf<'a>(&'a Type);
+
+impl VisitMut for ReplaceSelf<'_> {
+  fn visit_type_mut(&mut self, t: &mut Type) {
+    match t {
+      Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self") => *t = self.0.clone(),
+      _ => visit_mut::visit_type_mut(self, t),
+    }
+  }
+}
+
+impl Method {
+  pub fn new(i: &ItemImpl, f: &ImplItemFn) -> Self {
+    let self_ty = (*i.self_ty).clone();
+
+    let mut sig = f.sig.clone();
+    ReplaceSelf(&self_ty).visit_signature_mut(&mut sig);
+    sig.generics.params = i.generics.params.iter().chain(&f.sig.generics.params).cloned().collect();
+    sig.generics.where_clause = match (&i.generics.where_clause, &f.sig.generics.where_clause) {
+      (Some(a), Some(b)) => {
+        let mut w = a.clone();
+        w.predicates.extend(b.predicates.iter().cloned());
+        Some(w)
+      }
+      (a, b) => a.clone().or(b.clone()),
+    };
+
+    Self {
+      self_ty,
+      f: ItemFn {
+        attrs: vec![],
+        vis: f.vis.clone(),
+        sig,
+        block: Box::new(f.block.clone()),
+      },
+      generics: f.sig.generics.clone(),
+    }
+  }
+
+  /// Unique within a module, unlike the function's own name.
+  pub fn ident(&self) -> Ident {
+    method_ident(&self.self_ty, &self.f.sig.ident)
+  }
+}
+
+pub fn method_ident(self_ty: &Type, f: &Ident) -> Ident {
+  let ty = match self_ty {
+    Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+    _ => None,
+  };
+  format_ident!("{}_{}", ty.unwrap_or_default(), f)
+}
+
+fn is_marker(a: &Attribute) -> bool {
+  a.path().segments.last().is_some_and(|s| s.ident == "autowired")
+}
+
+fn returns_self(i: &ItemImpl, f: &ImplItemFn) -> bool {
+  let ReturnType::Type(_, t) = &f.sig.output else {
+    return false;
+  };
+  matches!(&**t, Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"))
+    || t.to_token_stream().to_string() == i.self_ty.to_token_stream().to_string()
+}
+
+/// Associated functions registered as providers: the ones marked with
+/// `#[autowired]`, or if there are none, every one returning `Self` without
+/// taking a receiver.
+///
+/// Fails if two of them provide the same type.
+pub fn autowired_methods(i: &ItemImpl) -> syn::Result<Vec<&ImplItemFn>> {
+  let fns = i.items.iter().filter_map(|item| match item {
+    ImplItem::Fn(f) => Some(f),
+    _ => None,
+  });
+
+  let marked = fns.clone().filter(|f| f.attrs.iter().any(is_marker)).collect::<Vec<_>>();
+  let methods = if marked.is_empty() {
+    fns
+      .filter(|f| f.sig.receiver().is_none() && returns_self(i, f))
+      .collect()
+  } else {
+    marked
+  };
+
+  let mut provided = HashMap::new();
+  for f in &methods {
+    let ReturnType::Type(_, ty) = &f.sig.output else {
+      continue;
+    };
+    let mut ty = (**ty).clone();
+    ReplaceSelf(&i.self_ty).visit_type_mut(&mut ty);
+    let name = ty.to_token_stream().to_string().split_whitespace().collect::<String>();
+
+    if let Some(first) = provided.insert(name.clone(), &f.sig.ident) {
+      return Err(Error::new_spanned(
+        &f.sig,
+        format!(
+          "`{}` would be provided by both `{}` and `{}`, mark the one to register with `#[autowired]`",
+          name, first, f.sig.ident
+        ),
+      ));
+    }
+  }
+
+  Ok(methods)
+}
+
+/// `#[inject(..)]` or `#[name = ".."]`, which say how an argument is resolved.
+pub fn is_arg_attr(a: &Attribute) -> bool {
+  a.path().segments.last().is_some_and(|s| s.ident == "inject") || a.path().is_ident("name")
+}
+
+/// Removes the markers and argument attributes the compiler would not understand.
+pub fn strip_impl(i: &mut ItemImpl) {
+  for item in i.items.iter_mut() {
+    let ImplItem::Fn(f) = item else {
+      continue;
+    };
+    f.attrs.retain(|a| !is_marker(a));
+    for arg in f.sig.inputs.iter_mut() {
+      if let FnArg::Typed(arg) = arg {
+        arg.attrs.retain(|a| !is_arg_attr(a) && !a.path().is_ident("runtime"));
+      }
+    }
+  }
+}
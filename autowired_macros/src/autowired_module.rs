@@ -0,0 +1,140 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+
+use crate::autowired_assisted::factory_ident;
+use crate::autowired_config::config_fn_ident;
+use crate::autowired_input::{autowired_methods, method_ident};
+use syn::{
+  parse::{Parse, ParseStream},
+  punctuated::Punctuated,
+  token::Comma,
+  AttrStyle, Attribute, Error, Item, ItemMod, Meta, ReturnType, Type,
+};
+
+pub fn registration_ident(ident: &Ident) -> Ident {
+  format_ident!("__AUTOWIRED_{}_registration", ident)
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+  attrs
+    .iter()
+    .any(|a| a.path().segments.last().is_some_and(|s| s.ident == name))
+}
+
+fn is_autowired(attrs: &[Attribute]) -> bool {
+  has_attr(attrs, "autowired")
+}
+
+/// `#[autowired(.., assisted)]`, which registers a factory instead.
+fn is_assisted(attrs: &[Attribute]) -> bool {
+  attrs
+    .iter()
+    .filter(|a| a.path().segments.last().is_some_and(|s| s.ident == "autowired"))
+    .filter_map(|a| a.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated).ok())
+    .any(|args| args.iter().any(|m| m.path().is_ident("assisted")))
+}
+
+/// The registration of a provider function returning `output`, `Self` being `self_ty`.
+fn fn_registration(attrs: &[Attribute], ident: &Ident, output: &ReturnType, self_ty: Option<&Type>) -> Option<Ident> {
+  if !is_assisted(attrs) {
+    return Some(registration_ident(&match self_ty {
+      Some(self_ty) => method_ident(self_ty, ident),
+      None => ident.clone(),
+    }));
+  }
+
+  let ReturnType::Type(_, ty) = output else {
+    return None;
+  };
+  let ty = match (&**ty, self_ty) {
+    (Type::Path(p), Some(self_ty)) if p.path.is_ident("Self") => self_ty,
+    (ty, _) => ty,
+  };
+  factory_ident(ty).map(|f| registration_ident(&f))
+}
+
+pub struct AutowiredModule {
+  pub module: ItemMod,
+  pub registrations: Vec<Ident>,
+}
+
+impl Parse for AutowiredModule {
+  fn parse(input: ParseStream) -> Result<Self, Error> {
+    let module = input.parse::<ItemMod>()?;
+
+    let Some((_, items)) = &module.content else {
+      return Err(Error::new_spanned(
+        &module,
+        "autowired_module! requires an inline module",
+      ));
+    };
+
+    let registrations = items
+      .iter()
+      .filter_map(|i| match i {
+        // generic providers are registered by the providers depending on them
+        Item::Struct(s) if is_autowired(&s.attrs) && s.generics.params.is_empty() => {
+          Some(vec![registration_ident(&s.ident)])
+        }
+        Item::Struct(s) if has_attr(&s.attrs, "autowired_config") => {
+          Some(vec![registration_ident(&config_fn_ident(&s.ident))])
+        }
+        Item::Enum(e) if is_autowired(&e.attrs) && e.generics.params.is_empty() => {
+          Some(vec![registration_ident(&e.ident)])
+        }
+        Item::Fn(f) if is_autowired(&f.attrs) && f.sig.generics.params.is_empty() => {
+          Some(fn_registration(&f.attrs, &f.sig.ident, &f.sig.output, None).into_iter().collect())
+        }
+        Item::Impl(i) if is_autowired(&i.attrs) && i.generics.params.is_empty() => Some(
+          // errors are reported by `#[autowired]` on the impl block
+          autowired_methods(i)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| f.sig.generics.params.is_empty())
+            .filter_map(|f| fn_registration(&i.attrs, &f.sig.ident, &f.sig.output, Some(&i.self_ty)))
+            .collect(),
+        ),
+        _ => None,
+      })
+      .flatten()
+      .collect();
+
+    Ok(Self {
+      module,
+      registrations,
+    })
+  }
+}
+
+impl ToTokens for AutowiredModule {
+  fn to_tokens(&self, tokens: &mut TokenStream2) {
+    let ItemMod {
+      attrs,
+      vis,
+      unsafety,
+      mod_token,
+      ident,
+      content,
+      ..
+    } = &self.module;
+    let (outer, inner): (Vec<_>, Vec<_>) = attrs
+      .iter()
+      .partition(|a| matches!(a.style, AttrStyle::Outer));
+    let items = content.iter().flat_map(|(_, items)| items);
+    let registrations = &self.registrations;
+
+    quote! {
+      #(#outer)*
+      #vis #unsafety #mod_token #ident {
+        #(#inner)*
+        #(#items)*
+
+        pub const MODULE: ::autowired::Module = ::autowired::Module {
+          name: module_path!(),
+          registrations: &[#(#registrations),*],
+        };
+      }
+    }
+    .to_tokens(tokens)
+  }
+}
@@ -1,134 +1,268 @@
 use autowired_data::AutowiredData;
-use autowired_input::AutowiredInput;
+use autowired_input::{autowired_methods, strip_impl, AutowiredInput, Method};
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::{
-  parse::Parse, ItemStruct, Token, Type, punctuated::Punctuated, token::Comma, bracketed, parse_macro_input,
-};
+use syn::{parse_macro_input, ItemImpl};
 
 mod autowired_input;
 mod autowired_args;
+mod autowired_assisted;
+mod autowired_config;
+mod autowired_context;
 mod autowired_data;
+mod autowired_module;
 
 use autowired_args::AutowiredArgs;
+use autowired_assisted::expand_assisted;
+use autowired_context::AutowiredContext;
+use autowired_module::AutowiredModule;
 
 
 #[proc_macro_attribute]
 pub fn autowired(args: TokenStream, input: TokenStream) -> TokenStream {
+  expand_autowired(args.into(), input.into()).into()
+}
+
+fn expand_autowired(args: TokenStream2, input: TokenStream2) -> TokenStream2 {
   let args = match AutowiredArgs::parse(args) {
       Ok(args) => args,
-      Err(e) => return e.write_errors().into(),
+      Err(e) => return e.write_errors(),
+  };
+
+  if let Ok(mut item_impl) = syn::parse2::<ItemImpl>(input.clone()) {
+    let methods = autowired_methods(&item_impl)
+      .map(|methods| methods.into_iter().map(|f| Method::new(&item_impl, f)).collect::<Vec<_>>());
+    strip_impl(&mut item_impl);
+    // the impl block is kept, so that its methods are not reported missing too
+    let methods = match methods {
+      Ok(methods) => methods,
+      Err(e) => {
+        let e = e.into_compile_error();
+        return quote!(#item_impl #e);
+      }
+    };
+
+    let providers = methods
+      .into_iter()
+      .map(|m| {
+        if args.assisted {
+          expand_assisted(args.clone(), AutowiredInput::Method(m))
+        } else {
+          expand(AutowiredData::new(args.clone(), AutowiredInput::Method(m))?)
+        }
+      })
+      .collect::<syn::Result<Vec<_>>>();
+    let providers = match providers {
+      Ok(providers) => providers,
+      Err(e) => return e.into_compile_error(),
+    };
+
+    return quote! {
+      #item_impl
+      #(#providers)*
+    };
+  }
+
+  let i = match syn::parse2::<AutowiredInput>(input) {
+    Ok(i) => i,
+    Err(e) => return e.into_compile_error(),
   };
 
-  let i = parse_macro_input!(input as AutowiredInput);
+  if args.assisted {
+    return expand_assisted(args, i).unwrap_or_else(|e| e.into_compile_error());
+  }
 
-  let input = AutowiredData::new(args, i);
+  match AutowiredData::new(args, i).and_then(expand) {
+    Ok(expanded) => expanded,
+    Err(e) => e.into_compile_error(),
+  }
+}
 
-  let type_ = input.typename().unwrap();
+fn expand(input: AutowiredData) -> syn::Result<TokenStream2> {
+  let type_ = input.typename()?;
   let name = type_.to_string().split_whitespace().collect::<String>();
-  let ident_name = uuid::Uuid::new_v4().as_simple().to_string();
-  
-  let children = input.children_names();
+  let ident = input.ident();
 
-  let type_id_name = format_ident!("_AUTOWIRED_{}_type_id", ident_name);
+  let children = input.dependency_edges();
+  let children_names = input.dependency_names();
+  let deferred = input.dependency_deferred();
+  let deferred_names = input.deferred_names();
+  let factories = input.dependency_factories();
+  let shared = input.dependency_shared();
+  let targets = input.dependency_targets();
 
-  let initializer_name = format_ident!("_AUTOWIRED_{}_initializer", ident_name);
+  let type_id_name = format_ident!("__AUTOWIRED_{}_type_id", ident);
+  let instantiations_name = format_ident!("__AUTOWIRED_{}_instantiations", ident);
+
+  let initializer_name = format_ident!("__AUTOWIRED_{}_initializer", ident);
   let initializer_body = input.initializer_body();
   let initializer_rt = input.initializer_rt();
 
+  let (impl_generics, _, _) = input.generics().split_for_impl();
+  let turbofish = input.turbofish();
+  let where_clause = input.where_clause()?;
+
+  let condition = input.condition();
+  let scoped = input.args.scoped;
   let dep_data_type = input.dep_data_type();
+  let registration_variant = input.registration_variant();
+  let registration = quote! {
+    #registration_variant(#dep_data_type {
+      name: #name,
+      children: &[#(::autowired::TypeId::of::<#children>),*],
+      children_names: &[#(#children_names),*],
+      deferred: &[#(::autowired::TypeId::of::<#deferred>),*],
+      deferred_names: &[#(#deferred_names),*],
+      factories: &[#(::autowired::TypeId::of::<#factories>),*],
+      shared: &[#((::autowired::TypeId::of::<#shared>, ::autowired::TypeId::of::<::std::sync::Arc<#shared>>)),*],
+      type_id: #type_id_name #turbofish,
+      instantiations: #instantiations_name #turbofish,
+      location: ::autowired::Location::caller(),
+      condition: #condition,
+      scoped: #scoped,
+      initializer: #initializer_name #turbofish,
+    })
+  };
+
+  let register = if input.is_generic() {
+    quote! {
+      impl #impl_generics ::autowired::GenericDep for #type_ #where_clause {
+        const REGISTRATION: ::autowired::Registration = #registration;
+      }
+    }
+  } else {
+    let registration_name = autowired_module::registration_ident(&input.ident());
+    let submit = if cfg!(feature = "inventory") {
+      quote! { ::autowired::submit! { #registration_name } }
+    } else {
+      quote!()
+    };
+
+    quote! {
+      #[doc(hidden)]
+      #[allow(non_upper_case_globals, dead_code)]
+      const #registration_name: ::autowired::Registration = #registration;
+
+      #submit
+    }
+  };
 
-  let typecheck_children = input.typecheck_children().unwrap();
+  let typecheck_children = input.typecheck_children()?;
 
-  let impl_autowired = input.impl_autowired().unwrap();
+  let impl_autowired = input.impl_autowired()?;
   let impl_clone = if input.args.clone {
     quote!(#[derive(Clone)])
   } else {
     quote!()
   };
 
-  quote! {
+  Ok(quote! {
     #impl_clone
     #input
 
     #impl_autowired
 
-    #[allow(non_upper_case_globals)]
-    fn #type_id_name() -> ::autowired::TypeId {
+    #[allow(non_snake_case)]
+    fn #type_id_name #impl_generics () -> ::autowired::TypeId #where_clause {
       ::autowired::TypeId::of::<#type_>()
     }
 
-    #[allow(non_upper_case_globals)]
-    fn #initializer_name(deps: &::autowired::Deps) -> #initializer_rt {
+    #[allow(non_snake_case)]
+    fn #initializer_name #impl_generics (deps: &::autowired::Deps) -> #initializer_rt #where_clause {
       #initializer_body
     }
 
-    ::autowired::submit! {
-      #dep_data_type {
-        name: #name,
-        children: &[#(#children),*],
-        type_id: #type_id_name,
-        initializer: #initializer_name,
-      }
+    #[allow(non_snake_case)]
+    fn #instantiations_name #impl_generics () -> Vec<::autowired::Registration> #where_clause {
+      use ::autowired::{ProbeConcrete as _, ProbeGeneric as _};
+
+      let registrations: Vec<Option<::autowired::Registration>> = vec![
+        #((&::autowired::Probe::<#targets>::new()).registration()),*
+      ];
+      registrations.into_iter().flatten().collect()
     }
 
+    #register
+
     #typecheck_children
 
-  }
-  .into()
+  })
 }
 
-struct ProviderInput {
-  p: Ident,
-  name: Ident,
-  types: Punctuated<Type, Comma>,
+/// Deserializes a struct from the context's `::autowired::config::Config`,
+/// at `prefix`, and provides it like `#[autowired]` does.
+#[proc_macro_attribute]
+pub fn autowired_config(args: TokenStream, input: TokenStream) -> TokenStream {
+  autowired_config::expand_autowired_config(args.into(), input.into()).into()
 }
 
-impl Parse for ProviderInput {
-  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-    let p = input.parse()?;
-    input.parse::<Token![~]>()?;
-    let name = input.parse()?;
-    
-    let content;
-    bracketed!(content in input);
-    
-    let types = content.parse_terminated(Type::parse, Comma)?;
-    Ok(Self { p, name, types })
-  }
+#[proc_macro]
+pub fn autowired_module(input: TokenStream) -> TokenStream {
+  let module = parse_macro_input!(input as AutowiredModule);
+  quote!(#module).into()
 }
 
-#[proc_macro_derive(Context)]
+#[proc_macro_derive(Context, attributes(modules, instantiate, conditional, scoped, bound, roots, skip_unused, profile, skip, flatten, import, name, provide))]
 pub fn derive_context(input: TokenStream) -> TokenStream {
-  let ItemStruct { ident, fields, .. } = parse_macro_input!(input);
-  let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
-  let names = fields.iter().enumerate().map(|(i, f)| match &f.ident {
-    Some(i) => quote!(#i),
-    None => quote!(#i),
-  });
-
-  quote!{
-    #[::autowired::async_trait]
-    impl ::autowired::Context for #ident {
-      fn get_initial_deps(&self) -> ::autowired::Deps {
-        let mut deps: ::autowired::DependencyMap = Default::default();
-        #(
-          {
-            let t = ::autowired::TypeId::of::<#types>();
-            deps.insert(t, Box::new(self.#names.clone()) as ::autowired::DependencyValue);
-          }
-        )*
-        ::autowired::Deps(deps)
-      }
+  let context = parse_macro_input!(input as AutowiredContext);
+  quote!(#context).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use proc_macro2::TokenStream as TokenStream2;
+  use quote::quote;
+
+  use crate::expand_autowired;
 
+  fn pretty(tokens: TokenStream2) -> String {
+    prettyplease::unparse(&syn::parse2(tokens).unwrap())
+  }
+
+  /// Expansions differ by whether providers are submitted to `inventory`.
+  fn snapshot_suffix() -> &'static str {
+    if cfg!(feature = "inventory") {
+      "inventory"
+    } else {
+      "modules"
     }
-    
-    #(
-    // impl ::autowired::Dep<#ident> for #types {}
-    // impl ::autowired::SharedDep<#ident> for #types {}
-    impl ::autowired::Dep<#ident> for #types {}
-    impl ::autowired::SharedDep<#ident> for #types {}
-    )*
-  }.into()
-}
\ No newline at end of file
+  }
+
+  #[test]
+  fn test_struct_expansion() {
+    let expand = || {
+      expand_autowired(
+        quote!(ctx = Ctx, clone),
+        quote! {
+          struct Repo {
+            db: Db,
+            #[inject(stateful())]
+            counter: usize,
+          }
+        },
+      )
+    };
+
+    assert_eq!(expand().to_string(), expand().to_string());
+    insta::with_settings!({ snapshot_suffix => snapshot_suffix() }, {
+      insta::assert_snapshot!(pretty(expand()));
+    });
+  }
+
+  #[test]
+  fn test_async_fn_expansion() {
+    let expanded = expand_autowired(
+      quote!(ctx = Ctx),
+      quote! {
+        async fn pool(cfg: Config) -> Pool {
+          Pool::connect(cfg).await
+        }
+      },
+    );
+
+    insta::with_settings!({ snapshot_suffix => snapshot_suffix() }, {
+      insta::assert_snapshot!(pretty(expanded));
+    });
+  }
+}
@@ -0,0 +1,85 @@
+use autowired::{DepData, DependencyValue, Deps, GraphSorter, Registration, TypeId};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+struct Zero;
+struct One;
+struct Leaf;
+struct Branch<T>(T);
+
+/// Type-level binary tree whose leaves are distinct types, one per provider.
+trait Tree {
+  fn type_ids<P: 'static>(out: &mut Vec<fn() -> TypeId>);
+}
+
+impl Tree for Leaf {
+  fn type_ids<P: 'static>(out: &mut Vec<fn() -> TypeId>) {
+    out.push(TypeId::of::<P>);
+  }
+}
+
+impl<T: Tree> Tree for Branch<T> {
+  fn type_ids<P: 'static>(out: &mut Vec<fn() -> TypeId>) {
+    T::type_ids::<(P, Zero)>(out);
+    T::type_ids::<(P, One)>(out);
+  }
+}
+
+type Tree8192 = Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Branch<Leaf>>>>>>>>>>>>>;
+
+fn instantiations() -> Vec<Registration> {
+  vec![]
+}
+
+fn initializer(_: &Deps) -> DependencyValue {
+  std::sync::Arc::new(())
+}
+
+/// `n` providers, each depending on up to three providers declared before it.
+fn graph(type_ids: &[fn() -> TypeId], n: usize) -> Vec<DepData> {
+  (0..n)
+    .map(|i| {
+      let mut children = vec![];
+      for c in [i.wrapping_sub(1), i / 2, i / 3] {
+        if c < i && !children.contains(&c) {
+          children.push(c);
+        }
+      }
+
+      DepData {
+        name: Box::leak(format!("T{i}").into_boxed_str()),
+        children: Box::leak(children.iter().map(|&c| type_ids[c]).collect()),
+        children_names: &[],
+        deferred: &[],
+        deferred_names: &[],
+        factories: &[],
+        shared: &[],
+        type_id: type_ids[i],
+        instantiations,
+        location: autowired::Location::caller(),
+        condition: Default::default(),
+        scoped: false,
+        initializer,
+      }
+    })
+    .rev()
+    .collect()
+}
+
+fn bench_sort(c: &mut Criterion) {
+  let mut type_ids = vec![];
+  Tree8192::type_ids::<()>(&mut type_ids);
+
+  for n in [1_000, 4_000, 8_000] {
+    let nodes = graph(&type_ids, n);
+    c.bench_function(&format!("sort {n} providers"), |b| {
+      b.iter_batched(
+        || nodes.clone(),
+        |nodes| GraphSorter::sort(black_box(nodes)),
+        BatchSize::LargeInput,
+      )
+    });
+  }
+}
+
+criterion_group!(benches, bench_sort);
+criterion_main!(benches);
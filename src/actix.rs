@@ -0,0 +1,156 @@
+use std::{
+  any::type_name,
+  future::{ready, Future, Ready},
+  marker::PhantomData,
+  ops::{Deref, DerefMut},
+  pin::Pin,
+  rc::Rc,
+  sync::Arc,
+};
+
+use actix_web::{
+  dev::{forward_ready, Payload, Service, ServiceFactory, ServiceRequest, ServiceResponse, Transform},
+  error::ErrorInternalServerError,
+  App, Error, FromRequest, HttpMessage, HttpRequest,
+};
+
+use crate::{Context, Deps, Provider};
+
+/// The dependencies `Inject` extracts from, whatever their context: the
+/// request's scope if [`RequestScope`] is installed, else the app's provider.
+#[derive(Clone)]
+struct InjectDeps(Arc<Deps>);
+
+/// Installs a [`Provider`] in an [`App`], for [`Inject`] and [`RequestScope`].
+pub trait AppExt {
+  fn provider<C: Context + 'static>(self, provider: Provider<C>) -> Self;
+}
+
+impl<T> AppExt for App<T>
+where
+  T: ServiceFactory<ServiceRequest, Config = (), Error = Error, InitError = ()>,
+{
+  fn provider<C: Context + 'static>(self, provider: Provider<C>) -> Self {
+    self
+      .app_data(InjectDeps(provider.deps().clone()))
+      .app_data(provider)
+  }
+}
+
+/// Extracts `T` from the request's scope, or from the app's provider, e.g.
+/// `Inject(users): Inject<UserService>`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inject<T>(pub T);
+
+impl<T: Clone + 'static> FromRequest for Inject<T> {
+  type Error = Error;
+  type Future = Ready<Result<Self, Error>>;
+
+  fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+    let deps = req
+      .extensions()
+      .get::<InjectDeps>()
+      .or_else(|| req.app_data::<InjectDeps>())
+      .cloned();
+
+    ready(match deps {
+      Some(deps) => deps
+        .0
+        .try_get_ref::<T>()
+        .map(|v| Inject(v.clone()))
+        .ok_or_else(|| ErrorInternalServerError(format!("`{}` is not provided", type_name::<T>()))),
+      None => Err(ErrorInternalServerError("no provider is installed, see `AppExt::provider`")),
+    })
+  }
+}
+
+impl<T> Deref for Inject<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T> DerefMut for Inject<T> {
+  fn deref_mut(&mut self) -> &mut T {
+    &mut self.0
+  }
+}
+
+/// Middleware building a child scope of the app's `Provider<C>` per request,
+/// so that `#[autowired(scoped)]` providers are built once per request.
+pub struct RequestScope<C> {
+  seed: fn(&ServiceRequest, &mut Deps),
+  _pd: PhantomData<fn() -> C>,
+}
+
+impl<C> RequestScope<C> {
+  /// Binds values taken from the request in its scope, e.g. the current user's id.
+  pub fn new(seed: fn(&ServiceRequest, &mut Deps)) -> Self {
+    Self { seed, _pd: PhantomData }
+  }
+}
+
+impl<C> Default for RequestScope<C> {
+  fn default() -> Self {
+    Self::new(|_, _| {})
+  }
+}
+
+impl<S, B, C> Transform<S, ServiceRequest> for RequestScope<C>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  C: Context + 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Transform = RequestScopeMiddleware<S, C>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, ()>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(RequestScopeMiddleware {
+      service: Rc::new(service),
+      seed: self.seed,
+      _pd: PhantomData,
+    }))
+  }
+}
+
+pub struct RequestScopeMiddleware<S, C> {
+  service: Rc<S>,
+  seed: fn(&ServiceRequest, &mut Deps),
+  _pd: PhantomData<fn() -> C>,
+}
+
+impl<S, B, C> Service<ServiceRequest> for RequestScopeMiddleware<S, C>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  C: Context + 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Error>>>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let service = self.service.clone();
+    let seed = self.seed;
+
+    Box::pin(async move {
+      let provider = req
+        .app_data::<Provider<C>>()
+        .cloned()
+        .ok_or_else(|| ErrorInternalServerError("no provider is installed, see `AppExt::provider`"))?;
+
+      let mut seeds = Deps::default();
+      seed(&req, &mut seeds);
+      let scope = provider.async_child(seeds).await;
+      req.extensions_mut().insert(InjectDeps(scope.deps().clone()));
+
+      service.call(req).await
+    })
+  }
+}
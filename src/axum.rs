@@ -0,0 +1,58 @@
+use std::{
+  convert::Infallible,
+  ops::{Deref, DerefMut},
+};
+
+use axum::{
+  async_trait,
+  extract::{FromRef, FromRequestParts},
+  http::request::Parts,
+};
+
+use crate::{Context, Dep, Provider};
+
+/// Router state holding a [`Provider`] of `Self::Context`, extracted with `FromRef`.
+///
+/// Implemented by `Provider` itself; implement it for your own state struct,
+/// together with `FromRef`, to keep other state next to the provider.
+pub trait ProviderState: Send + Sync {
+  type Context: Context + 'static;
+}
+
+impl<C: Context + 'static> ProviderState for Provider<C> {
+  type Context = C;
+}
+
+/// Extracts `T` from the router state's provider, e.g. `Inject(users): Inject<UserService>`.
+///
+/// `T` must be provided by the state's context, which is checked at compile time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inject<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Inject<T>
+where
+  S: ProviderState,
+  Provider<S::Context>: FromRef<S>,
+  T: Dep<S::Context> + Clone + 'static,
+{
+  type Rejection = Infallible;
+
+  async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+    Ok(Inject(Provider::from_ref(state).provide()))
+  }
+}
+
+impl<T> Deref for Inject<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T> DerefMut for Inject<T> {
+  fn deref_mut(&mut self) -> &mut T {
+    &mut self.0
+  }
+}
@@ -0,0 +1,106 @@
+use std::{
+  any::{type_name, TypeId},
+  collections::HashMap,
+  panic::Location,
+  pin::Pin,
+  sync::{Arc, LazyLock, Mutex, PoisonError},
+};
+
+use crate::{ADepData, DepData, DependencyValue, Deps, Future, Registration};
+
+/// Dependencies declared by a runtime binding, as a tuple of types, e.g. `(Db, Config)`.
+pub trait Dependencies: 'static {
+  const TYPE_IDS: &'static [fn() -> TypeId];
+
+  fn names() -> Vec<&'static str>;
+}
+
+macro_rules! impl_dependencies {
+  ($($t:ident),*) => {
+    impl<$($t: 'static),*> Dependencies for ($($t,)*) {
+      const TYPE_IDS: &'static [fn() -> TypeId] = &[$(TypeId::of::<$t>),*];
+
+      fn names() -> Vec<&'static str> {
+        vec![$(type_name::<$t>()),*]
+      }
+    }
+  };
+}
+
+impl_dependencies!();
+impl_dependencies!(A);
+impl_dependencies!(A, B);
+impl_dependencies!(A, B, C);
+impl_dependencies!(A, B, C, D);
+impl_dependencies!(A, B, C, D, E);
+impl_dependencies!(A, B, C, D, E, F);
+impl_dependencies!(A, B, C, D, E, F, G);
+impl_dependencies!(A, B, C, D, E, F, G, H);
+
+/// How a runtime binding of `T` is built, stored in `Deps` under `Binding<T>`'s
+/// `TypeId` for its registration's initializer to find.
+pub(crate) enum Binding<T> {
+  Value(DependencyValue),
+  Fn(Box<dyn Fn(&Deps) -> T + Send + Sync>),
+}
+
+type AsyncBindingFn<T> = dyn Fn(&Deps) -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync;
+
+pub(crate) struct AsyncBinding<T>(pub Box<AsyncBindingFn<T>>);
+
+fn initializer<T: Send + Sync + 'static>(deps: &Deps) -> DependencyValue {
+  match deps.get_ref::<Binding<T>>() {
+    Binding::Value(v) => v.clone(),
+    Binding::Fn(f) => Arc::new(f(deps)),
+  }
+}
+
+fn async_initializer<T: Send + Sync + 'static>(deps: &Deps) -> Pin<Box<dyn Future<Output = DependencyValue> + Send + '_>> {
+  let fut = (deps.get_ref::<AsyncBinding<T>>().0)(deps);
+  Box::pin(async move { Arc::new(fut.await) as DependencyValue })
+}
+
+/// The names of `D`, leaked once per tuple type, as `type_name` is not const.
+fn names<D: Dependencies>() -> &'static [&'static str] {
+  static NAMES: LazyLock<Mutex<HashMap<TypeId, &'static [&'static str]>>> = LazyLock::new(Default::default);
+  let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+  names
+    .entry(TypeId::of::<D>())
+    .or_insert_with(|| Box::leak(D::names().into_boxed_slice()))
+}
+
+pub(crate) fn registration<T: Send + Sync + 'static, D: Dependencies>(location: &'static Location<'static>) -> Registration {
+  Registration::Sync(DepData {
+    name: type_name::<T>(),
+    children: D::TYPE_IDS,
+    children_names: names::<D>(),
+    deferred: &[],
+    deferred_names: &[],
+    factories: &[],
+    shared: &[],
+    type_id: TypeId::of::<T>,
+    instantiations: Vec::new,
+    location,
+    condition: Default::default(),
+    scoped: false,
+    initializer: initializer::<T>,
+  })
+}
+
+pub(crate) fn async_registration<T: Send + Sync + 'static, D: Dependencies>(location: &'static Location<'static>) -> Registration {
+  Registration::Async(ADepData {
+    name: type_name::<T>(),
+    children: D::TYPE_IDS,
+    children_names: names::<D>(),
+    deferred: &[],
+    deferred_names: &[],
+    factories: &[],
+    shared: &[],
+    type_id: TypeId::of::<T>,
+    instantiations: Vec::new,
+    location,
+    condition: Default::default(),
+    scoped: false,
+    initializer: async_initializer::<T>,
+  })
+}
@@ -0,0 +1,205 @@
+use std::{
+  fmt::{self, Display},
+  fs,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use serde_path_to_error::Segment;
+
+/// Configuration sources, merged into one tree which `#[autowired_config]`
+/// structs are deserialized from.
+///
+/// Later sources override earlier ones, key by key. Bind it as a `Context`
+/// field so that config providers can depend on it.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+  value: Arc<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Toml,
+  Json,
+  Yaml,
+}
+
+impl Format {
+  /// The format of a file, by its extension.
+  pub fn of(path: &Path) -> Option<Self> {
+    match path.extension()?.to_str()? {
+      "toml" => Some(Self::Toml),
+      "json" => Some(Self::Json),
+      "yaml" | "yml" => Some(Self::Yaml),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+  Io { path: PathBuf, source: std::io::Error },
+  UnknownFormat { path: PathBuf },
+  Parse { source: String, message: String },
+  /// A key is missing or has an invalid value; `path` is its full dotted path.
+  Invalid { path: String, message: String },
+}
+
+impl Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Io { path, source } => write!(f, "cannot read config file {}: {}", path.display(), source),
+      Self::UnknownFormat { path } => write!(f, "unknown config file format: {}", path.display()),
+      Self::Parse { source, message } => write!(f, "cannot parse config {}: {}", source, message),
+      Self::Invalid { path, message } => write!(f, "invalid config at `{}`: {}", path, message),
+    }
+  }
+}
+
+impl std::error::Error for ConfigError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::Io { source, .. } => Some(source),
+      _ => None,
+    }
+  }
+}
+
+fn merge(into: &mut Value, from: Value) {
+  match (into, from) {
+    (Value::Object(into), Value::Object(from)) => {
+      for (k, v) in from {
+        merge(into.entry(k).or_insert(Value::Null), v);
+      }
+    }
+    (into, from) => *into = from,
+  }
+}
+
+impl Config {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_file(self, path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+    let path = path.as_ref();
+    let format = Format::of(path).ok_or_else(|| ConfigError::UnknownFormat { path: path.into() })?;
+    let source = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+      path: path.into(),
+      source,
+    })?;
+
+    self.with_source(&path.display().to_string(), format, &source)
+  }
+
+  /// Merges `source`, named `name` in errors.
+  pub fn with_source(mut self, name: &str, format: Format, source: &str) -> Result<Self, ConfigError> {
+    let value = match format {
+      Format::Toml => toml::from_str::<Value>(source).map_err(|e| e.to_string()),
+      Format::Json => serde_json::from_str::<Value>(source).map_err(|e| e.to_string()),
+      Format::Yaml => serde_yaml::from_str::<Value>(source).map_err(|e| e.to_string()),
+    }
+    .map_err(|message| ConfigError::Parse {
+      source: name.into(),
+      message,
+    })?;
+
+    merge(Arc::make_mut(&mut self.value), value);
+    Ok(self)
+  }
+
+  /// Merges the environment variables starting with `{prefix}__`, e.g.
+  /// `APP__DATABASE__POOL_SIZE=20` sets `database.pool_size` for prefix `APP`.
+  pub fn with_env(self, prefix: &str) -> Self {
+    self.with_vars(prefix, std::env::vars())
+  }
+
+  /// Like [`Config::with_env`], reading `vars` instead of the environment.
+  pub fn with_vars(mut self, prefix: &str, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+    let prefix = format!("{}__", prefix);
+
+    for (k, v) in vars {
+      let Some(path) = k.strip_prefix(&prefix) else {
+        continue;
+      };
+      // kept as a string, which `Config::get` reads as a number or a boolean where expected
+      let mut value = Value::String(v);
+      for key in path.rsplit("__") {
+        value = Value::Object(Map::from_iter([(key.to_lowercase(), value)]));
+      }
+      merge(Arc::make_mut(&mut self.value), value);
+    }
+
+    self
+  }
+
+  /// Deserializes the table at dotted path `prefix`, or the whole tree if it is empty.
+  ///
+  /// Strings such as `"20"` or `"true"`, e.g. from environment variables, are
+  /// read as numbers and booleans where the target field is not a string.
+  pub fn get<T: DeserializeOwned>(&self, prefix: &str) -> Result<T, ConfigError> {
+    let mut value = prefix
+      .split('.')
+      .filter(|k| !k.is_empty())
+      .try_fold(&*self.value, |v, k| v.get(k))
+      .cloned()
+      .ok_or_else(|| ConfigError::Invalid {
+        path: prefix.into(),
+        message: "missing table".into(),
+      })?;
+
+    let e = loop {
+      match serde_path_to_error::deserialize(value.clone()) {
+        Ok(t) => return Ok(t),
+        Err(e) if parse_string(&mut value, e.path()) => continue,
+        Err(e) => break e,
+      }
+    };
+
+    let message = e.inner().to_string();
+    let mut path = e.path().to_string();
+    // serde reports a missing field at its struct, rather than at the field
+    if let Some(field) = message.strip_prefix("missing field `").and_then(|m| m.split('`').next()) {
+      path = match path.as_str() {
+        "." => field.to_string(),
+        p => format!("{}.{}", p, field),
+      };
+    }
+    let path = match path.as_str() {
+      "." => prefix.to_string(),
+      p if prefix.is_empty() => p.to_string(),
+      p => format!("{}.{}", prefix, p),
+    };
+    Err(ConfigError::Invalid { path, message })
+  }
+}
+
+/// Replaces the string at `path` by the number or boolean it spells, if any.
+fn parse_string(value: &mut Value, path: &serde_path_to_error::Path) -> bool {
+  let mut value = value;
+  for segment in path.iter() {
+    let next = match segment {
+      Segment::Map { key } => value.get_mut(key.as_str()),
+      Segment::Seq { index } => value.get_mut(*index),
+      _ => None,
+    };
+    match next {
+      Some(v) => value = v,
+      None => return false,
+    }
+  }
+
+  let parsed = match value {
+    Value::String(s) => serde_json::from_str::<Value>(s).ok(),
+    _ => None,
+  };
+  match parsed {
+    Some(v @ (Value::Number(_) | Value::Bool(_))) => {
+      *value = v;
+      true
+    }
+    _ => false,
+  }
+}
@@ -0,0 +1,52 @@
+use std::{
+  any::type_name,
+  fmt::{self, Display},
+  future::Future,
+  sync::Arc,
+};
+
+use crate::{Context, Deps, Provider};
+
+tokio::task_local! {
+  static CURRENT: Arc<Deps>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrentError {
+  /// Not called within [`Provider::scope`].
+  NoScope,
+  /// The current provider does not provide the type, named here.
+  NotProvided(&'static str),
+}
+
+impl Display for CurrentError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::NoScope => write!(f, "`autowired::current` called outside of `Provider::scope`"),
+      Self::NotProvided(name) => write!(f, "`{}` is not provided by the current provider", name),
+    }
+  }
+}
+
+impl std::error::Error for CurrentError {}
+
+impl<C: Context + ?Sized> Provider<C> {
+  /// Runs `fut` with this provider as the current one, see [`current`].
+  pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+    CURRENT.scope(self.deps().clone(), fut).await
+  }
+}
+
+/// The `T` provided by the current provider, see [`Provider::scope`].
+///
+/// Panics outside of a scope, or if `T` is not provided; see [`try_current`].
+pub fn current<T: Clone + 'static>() -> T {
+  try_current().unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn try_current<T: Clone + 'static>() -> Result<T, CurrentError> {
+  CURRENT
+    .try_with(|deps| deps.try_get_ref::<T>().cloned())
+    .map_err(|_| CurrentError::NoScope)?
+    .ok_or(CurrentError::NotProvided(type_name::<T>()))
+}
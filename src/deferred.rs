@@ -0,0 +1,127 @@
+use std::{
+  any::{type_name, TypeId},
+  collections::HashMap,
+  marker::PhantomData,
+  sync::{Arc, OnceLock, Weak},
+};
+
+use crate::{DependencyValue, Deps};
+
+/// The dependencies `Lazy` and `Factory` resolve from, set once they are built.
+///
+/// Weak, as the dependencies usually contain the `Lazy` and `Factory` values.
+pub(crate) struct Handle {
+  deps: OnceLock<Weak<Deps>>,
+  initializers: HashMap<TypeId, fn(&Deps) -> DependencyValue>,
+}
+
+impl Handle {
+  /// Binds a handle in `deps`, with the initializers of the handle it may replace, e.g. a parent scope's.
+  pub(crate) fn insert(deps: &mut Deps, initializers: impl IntoIterator<Item = (TypeId, fn(&Deps) -> DependencyValue)>) {
+    let mut all = deps.try_get_ref::<Handle>().map(|h| h.initializers.clone()).unwrap_or_default();
+    all.extend(initializers);
+    deps.insert(Handle {
+      deps: OnceLock::new(),
+      initializers: all,
+    });
+  }
+
+  /// Lets the handle bound in `deps` resolve from them.
+  pub(crate) fn bind(deps: &Arc<Deps>) {
+    if let Some(h) = deps.try_get_ref::<Handle>() {
+      let _ = h.deps.set(Arc::downgrade(deps));
+    }
+  }
+
+  fn deps(&self) -> Arc<Deps> {
+    self
+      .deps
+      .get()
+      .expect("`Lazy` and `Factory` dependencies cannot be resolved while they are being built")
+      .upgrade()
+      .expect("`Lazy` and `Factory` dependencies cannot be resolved once their provider is dropped")
+  }
+}
+
+impl Deps {
+  fn handle(&self) -> Arc<Handle> {
+    self
+      .0
+      .get(&TypeId::of::<Handle>())
+      .cloned()
+      .and_then(|h| h.downcast::<Handle>().ok())
+      .expect("`Lazy` and `Factory` dependencies are only resolved by providers")
+  }
+
+  pub fn lazy<T>(&self) -> Lazy<T> {
+    Lazy {
+      handle: self.handle(),
+      value: OnceLock::new(),
+    }
+  }
+
+  pub fn factory<T>(&self) -> Factory<T> {
+    Factory {
+      handle: self.handle(),
+      _pd: PhantomData,
+    }
+  }
+}
+
+/// A provided `T`, resolved on first use rather than when the dependent is built,
+/// so that `T` may depend on the dependent in turn.
+pub struct Lazy<T> {
+  handle: Arc<Handle>,
+  value: OnceLock<Arc<T>>,
+}
+
+impl<T: Send + Sync + 'static> Lazy<T> {
+  pub fn get(&self) -> &T {
+    self.value.get_or_init(|| self.handle.deps().get_arc())
+  }
+}
+
+impl<T> Clone for Lazy<T> {
+  fn clone(&self) -> Self {
+    Lazy {
+      handle: self.handle.clone(),
+      value: self.value.clone(),
+    }
+  }
+}
+
+/// Constructs a new `T` on each call to [`Factory::get`], with `T`'s provider.
+///
+/// `T`'s provider must be synchronous.
+///
+/// The [`crate::Provider`] itself cannot be injected, as the dependencies it
+/// owns would own it in turn: take a `Factory<T>` or a `Lazy<T>` of each type
+/// to resolve later instead.
+pub struct Factory<T> {
+  handle: Arc<Handle>,
+  _pd: PhantomData<fn() -> T>,
+}
+
+impl<T: Send + Sync + 'static> Factory<T> {
+  pub fn get(&self) -> T {
+    let initializer = self
+      .handle
+      .initializers
+      .get(&TypeId::of::<T>())
+      .unwrap_or_else(|| panic!("no synchronous provider of `{}` is active", type_name::<T>()));
+
+    let value = initializer(&self.handle.deps())
+      .downcast::<T>()
+      .unwrap_or_else(|_| panic!("downcast error: {}", type_name::<T>()));
+    Arc::try_unwrap(value).unwrap_or_else(|_| panic!("a new `{}` is shared", type_name::<T>()))
+  }
+}
+
+impl<T> Clone for Factory<T> {
+  fn clone(&self) -> Self {
+    Factory {
+      handle: self.handle.clone(),
+      _pd: PhantomData,
+    }
+  }
+}
@@ -1,12 +1,24 @@
-use std::any::{type_name, TypeId};
+use std::{
+  any::{type_name, TypeId},
+  collections::HashMap,
+  sync::Arc,
+};
 
 use crate::DependencyMap;
 
 #[derive(Default)]
 pub struct Deps(pub DependencyMap);
 
+/// Values of type `T` bound by name, stored in `Deps` under `Named<T>`'s `TypeId`.
+struct Named<T>(HashMap<&'static str, T>);
+
 impl Deps {
   pub fn get<T: Clone + 'static>(&self) -> T {
+    self.get_ref::<T>().clone()
+  }
+
+  /// Borrows the provided `T`, without cloning it.
+  pub fn get_ref<T: 'static>(&self) -> &T {
     let t = TypeId::of::<T>();
 
     let v = self
@@ -14,12 +26,53 @@ impl Deps {
       .get(&t)
       .unwrap_or_else(|| panic!("get error: {}", type_name::<T>()));
 
-    if let Some(v) = v.downcast_ref::<T>() {
-      v.clone()
-    } else if let Some(v) = v.downcast_ref::<Box<T>>() {
-      *v.clone()
-    } else {
-      panic!("downcast error: {}", type_name::<T>())
+    v.downcast_ref::<T>()
+      .unwrap_or_else(|| panic!("downcast error: {}", type_name::<T>()))
+  }
+
+  /// Like [`Deps::get_ref`], or `None` if `T` is not provided.
+  pub fn try_get_ref<T: 'static>(&self) -> Option<&T> {
+    self.0.get(&TypeId::of::<T>()).and_then(|v| v.downcast_ref::<T>())
+  }
+
+  /// Binds `value` as `T`, e.g. to seed a child scope, see [`crate::Provider::child`].
+  pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+    self.0.insert(TypeId::of::<T>(), Arc::new(value));
+  }
+
+  /// Shares the provided `T`, or clones a bound `Arc<T>`, e.g. a context field.
+  pub fn get_arc<T: Send + Sync + 'static>(&self) -> Arc<T> {
+    if let Some(v) = self.0.get(&TypeId::of::<T>()) {
+      return v
+        .clone()
+        .downcast::<T>()
+        .unwrap_or_else(|_| panic!("downcast error: {}", type_name::<T>()));
     }
+
+    self.get::<Arc<T>>()
+  }
+
+  /// Gets the `T` bound as `name`, e.g. by a `#[name = ".."]` context field.
+  pub fn get_named<T: Clone + 'static>(&self, name: &str) -> T {
+    self
+      .0
+      .get(&TypeId::of::<Named<T>>())
+      .and_then(|v| v.downcast_ref::<Named<T>>())
+      .and_then(|n| n.0.get(name))
+      .unwrap_or_else(|| panic!("get error: {} named {:?}", type_name::<T>(), name))
+      .clone()
+  }
+
+  pub fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, value: T) {
+    let named = self
+      .0
+      .entry(TypeId::of::<Named<T>>())
+      .or_insert_with(|| Arc::new(Named::<T>(HashMap::new())));
+
+    Arc::get_mut(named)
+      .and_then(|n| n.downcast_mut::<Named<T>>())
+      .expect("named bindings are inserted before being shared")
+      .0
+      .insert(name, value);
   }
 }
@@ -1,56 +1,512 @@
-use crate::{graph_sorter::GraphSorter, ADepData, DepData, Deps};
+use std::{
+  any::TypeId,
+  cell::OnceCell,
+  collections::{HashMap, HashSet},
+};
+
+#[cfg(not(feature = "inventory"))]
+use crate::Module;
+use crate::{
+  deferred::Handle, graph_sorter::GraphSorter, ADepData, DepData, Deps, IDepData, ProviderInfo, Registration,
+  ValidationError, ValidationReport,
+};
 
 pub struct DepsBuilder {
   deps: Deps,
+  overridden: HashSet<TypeId>,
+  instantiations: Vec<Registration>,
+  profile: Option<String>,
+  scope: Option<Vec<Registration>>,
+  bindings: Vec<Registration>,
+  /// Types bound outside of providers, see [`DepsBuilder::with_bound`].
+  bound: HashSet<TypeId>,
+  roots: Vec<TypeId>,
+  skip_unused: bool,
+  /// The only types to build, along with their dependencies, see [`DepsBuilder::with_targets`].
+  targets: Option<Vec<TypeId>>,
+  /// The active providers, computed once as conditions may not be pure.
+  registrations: OnceCell<Vec<Registration>>,
+  #[cfg(not(feature = "inventory"))]
+  modules: Vec<&'static Module>,
 }
 
 impl DepsBuilder {
   pub fn new(deps: Deps) -> Self {
-    Self { deps }
+    Self {
+      deps,
+      overridden: HashSet::new(),
+      instantiations: Vec::new(),
+      profile: None,
+      scope: None,
+      bindings: Vec::new(),
+      bound: HashSet::new(),
+      roots: Vec::new(),
+      skip_unused: false,
+      targets: None,
+      registrations: OnceCell::new(),
+      #[cfg(not(feature = "inventory"))]
+      modules: Vec::new(),
+    }
   }
 
-  fn _build_sync(&mut self) {
-    let dep_data = inventory::iter::<DepData>
-      .into_iter()
+  /// Registers instantiations of generic providers which no other provider depends on.
+  pub fn with_instantiations(mut self, instantiations: Vec<Registration>) -> Self {
+    self.instantiations = instantiations;
+    self
+  }
+
+  /// Builds only the conditional providers whose condition is met under `profile`.
+  pub fn with_profile(mut self, profile: Option<&str>) -> Self {
+    self.profile = profile.map(Into::into);
+    self
+  }
+
+  /// Registers the providers of `modules`; a module imported by several contexts is registered once.
+  #[cfg(not(feature = "inventory"))]
+  pub fn with_modules(mut self, modules: Vec<&'static Module>) -> Self {
+    for m in modules {
+      if !self.modules.iter().any(|r| std::ptr::eq(*r, m)) {
+        self.modules.push(m);
+      }
+    }
+    self
+  }
+
+  /// Builds the scoped providers among `registrations`, see [`DepsBuilder::scoped`],
+  /// instead of the registered unscoped ones.
+  pub fn with_scope(mut self, registrations: Vec<Registration>) -> Self {
+    self.scope = Some(registrations);
+    self
+  }
+
+  /// Registers runtime bindings along with the registered providers, `bound` holding how they are built.
+  pub fn with_bindings(mut self, bindings: Vec<Registration>, bound: Deps) -> Self {
+    self.bindings.extend(bindings);
+    self.deps.0.extend(bound.0);
+    self
+  }
+
+  /// Types which are bound later, e.g. per child scope, so they are not
+  /// reported missing by [`DepsBuilder::validate`].
+  pub fn with_bound(mut self, types: Vec<TypeId>) -> Self {
+    self.bound.extend(types);
+    self
+  }
+
+  /// Providers which none of `roots` depends on, even transitively or through
+  /// `Lazy` and `Factory`, are unused; they are not built if `skip_unused`.
+  ///
+  /// Generic provider instantiations and runtime bindings are roots too, as
+  /// they are registered explicitly.
+  pub fn with_roots(mut self, roots: Vec<TypeId>, skip_unused: bool) -> Self {
+    self.roots = roots;
+    self.skip_unused = skip_unused;
+    self
+  }
+
+  /// Builds only the providers which `targets` depend on, even transitively
+  /// or through `Lazy` and `Factory`, rather than every registered one;
+  /// the other providers are neither checked nor built.
+  pub fn with_targets(mut self, targets: Vec<TypeId>) -> Self {
+    self.targets = Some(targets);
+    self
+  }
+
+  /// Inserts `overrides` up front; providers of these types are never initialized.
+  pub fn with_overrides(mut self, overrides: Deps) -> Self {
+    for (t, v) in overrides.0 {
+      self.overridden.insert(t);
+      self.deps.0.insert(t, v);
+    }
+    self
+  }
+
+  /// Whether `t` is bound or has a registered provider, active or not,
+  /// including the generic instantiations providers need.
+  pub fn provides(&self, t: TypeId) -> bool {
+    if self.deps.0.contains_key(&t) || self.bound.contains(&t) {
+      return true;
+    }
+    let mut registrations = self
+      ._registered()
       .cloned()
+      .chain(self.bindings.iter().cloned())
+      .chain(self.instantiations.iter().cloned())
       .collect::<Vec<_>>();
+    let mut registered = registrations.iter().map(Registration::type_id).collect::<HashSet<_>>();
+    let mut i = 0;
+    while i < registrations.len() {
+      for r in registrations[i].instantiations() {
+        if registered.insert(r.type_id()) {
+          registrations.push(r);
+        }
+      }
+      i += 1;
+    }
+    registered.contains(&t)
+  }
 
-    let dep_data = GraphSorter::sort(dep_data);
+  #[cfg(feature = "inventory")]
+  fn _registered(&self) -> impl Iterator<Item = &'static Registration> {
+    inventory::iter::<Registration>.into_iter()
+  }
 
-    for dep in dep_data {
-      let dep_type = (dep.type_id)();
-      let initialized_dep = (dep.initializer)(&self.deps);
-      self.deps.0.insert(dep_type, initialized_dep);
+  #[cfg(not(feature = "inventory"))]
+  fn _registered(&self) -> impl Iterator<Item = &'static Registration> + '_ {
+    self.modules.iter().flat_map(|m| m.registrations)
+  }
+
+  fn _is_active(&self, r: &Registration) -> bool {
+    r.dep_data().condition().is_met(self.profile.as_deref())
+  }
+
+  /// Active scoped providers, which are built per child scope rather than by [`DepsBuilder::build`].
+  pub fn scoped(&self) -> Vec<Registration> {
+    let registrations = self._registrations();
+    let unused = self._skipped_unused(registrations);
+    registrations
+      .iter()
+      .filter(|r| r.dep_data().scoped() && !unused.contains(&r.type_id()))
+      .cloned()
+      .collect()
+  }
+
+  /// Types of the providers in `registrations` which are unused, see [`DepsBuilder::with_roots`].
+  fn _unused(&self, registrations: &[Registration]) -> HashSet<TypeId> {
+    if self.roots.is_empty() {
+      return HashSet::new();
+    }
+
+    let roots = self
+      .roots
+      .iter()
+      .copied()
+      .chain(self.instantiations.iter().chain(&self.bindings).map(Registration::type_id));
+    let used = Self::_used(roots, registrations);
+    registrations
+      .iter()
+      .map(Registration::type_id)
+      .filter(|t| !used.contains(t))
+      .collect()
+  }
+
+  /// `roots` and the types they depend on in `registrations`, even through `Lazy` and `Factory`.
+  fn _used(roots: impl IntoIterator<Item = TypeId>, registrations: &[Registration]) -> HashSet<TypeId> {
+    let mut edges = HashMap::<TypeId, Vec<TypeId>>::new();
+    for d in registrations.iter().map(Registration::dep_data) {
+      let children = d.children().iter().chain(d.deferred()).map(|c| c());
+      edges.entry(d.type_id()).or_default().extend(children);
     }
+
+    let mut stack = roots.into_iter().collect::<Vec<_>>();
+    let mut used = HashSet::new();
+    while let Some(t) = stack.pop() {
+      if used.insert(t) {
+        stack.extend(edges.get(&t).into_iter().flatten());
+      }
+    }
+    used
   }
 
-  async fn _build_async(&mut self) {
-    let dep_data = inventory::iter::<ADepData>
+  /// Unused providers which are not built, see [`DepsBuilder::with_roots`].
+  fn _skipped_unused(&self, registrations: &[Registration]) -> HashSet<TypeId> {
+    if self.skip_unused && self.scope.is_none() && self.targets.is_none() {
+      self._unused(registrations)
+    } else {
+      HashSet::new()
+    }
+  }
+
+  /// Active registered providers, along with every generic provider instantiation they need.
+  ///
+  /// Panics if they cannot be built, see [`DepsBuilder::validate`]; missing
+  /// dependencies are left to fail on [`Deps::get`], as overridden providers
+  /// are never built.
+  fn _registrations(&self) -> &[Registration] {
+    self.registrations.get_or_init(|| self._check_registrations())
+  }
+
+  fn _check_registrations(&self) -> Vec<Registration> {
+    if let Some(scope) = &self.scope {
+      return scope.clone();
+    }
+
+    let (active, inactive) = self._partition();
+    let error = self
+      ._errors(&active, &inactive)
       .into_iter()
+      .find(|e| !matches!(e, ValidationError::Missing { .. }));
+    if let Some(e) = error {
+      panic!("{}", e);
+    }
+    active
+  }
+
+  /// Active and inactive registered providers, the active ones along with
+  /// every generic provider instantiation they need, and restricted to the
+  /// ones the targets need if any.
+  fn _partition(&self) -> (Vec<Registration>, Vec<Registration>) {
+    let (mut registrations, inactive): (Vec<_>, Vec<_>) = self
+      ._registered()
       .cloned()
+      .chain(self.bindings.iter().cloned())
+      .partition(|r| self._is_active(r));
+    let mut registered = registrations
+      .iter()
+      .map(Registration::type_id)
+      .collect::<HashSet<_>>();
+    for r in &self.instantiations {
+      if self._is_active(r) && registered.insert(r.type_id()) {
+        registrations.push(r.clone());
+      }
+    }
+
+    let mut i = 0;
+    while i < registrations.len() {
+      for r in registrations[i].instantiations() {
+        if self._is_active(&r) && registered.insert(r.type_id()) {
+          registrations.push(r);
+        }
+      }
+      i += 1;
+    }
+
+    if let Some(targets) = &self.targets {
+      let used = Self::_used(targets.iter().copied(), &registrations);
+      registrations.retain(|r| used.contains(&r.type_id()));
+    }
+
+    (registrations, inactive)
+  }
+
+  fn _errors(&self, active: &[Registration], inactive: &[Registration]) -> Vec<ValidationError> {
+    let mut errors = self._check_candidates(active, inactive);
+    errors.extend(Self::_check_scopes(active));
+    errors
+  }
+
+  /// Checks the providers which would be built without building them: unlike
+  /// [`DepsBuilder::build`], also reports missing dependencies and every error.
+  pub fn validate(&self) -> Result<ValidationReport, Vec<ValidationError>> {
+    let info = |r: &Registration| ProviderInfo {
+      name: r.dep_data().name(),
+      location: r.dep_data().location(),
+    };
+    let (active, inactive) = self._partition();
+    let mut errors = self._errors(&active, &inactive);
+
+    let sorted = match GraphSorter::try_sort(active) {
+      Ok(sorted) => sorted,
+      Err(cycle) => {
+        errors.push(ValidationError::Cycle { cycle });
+        vec![]
+      }
+    };
+    if !errors.is_empty() {
+      return Err(errors);
+    }
+
+    let unused = self._unused(&sorted);
+    let (scoped, providers): (Vec<_>, Vec<_>) = sorted.iter().partition(|r| r.dep_data().scoped());
+    Ok(ValidationReport {
+      providers: providers.into_iter().map(info).collect(),
+      scoped: scoped.into_iter().map(info).collect(),
+      inactive: inactive.iter().map(info).collect(),
+      unused: sorted.iter().filter(|r| unused.contains(&r.type_id())).map(info).collect(),
+    })
+  }
+
+  /// Unscoped providers depending on scoped ones, which are built later.
+  fn _check_scopes(registrations: &[Registration]) -> Vec<ValidationError> {
+    let scoped = registrations
+      .iter()
+      .filter(|r| r.dep_data().scoped())
+      .map(Registration::type_id)
+      .collect::<HashSet<_>>();
+
+    let mut errors = vec![];
+    for d in registrations.iter().map(Registration::dep_data).filter(|d| !d.scoped()) {
+      for (c, name) in d.children().iter().zip(d.children_names()) {
+        if scoped.contains(&c()) {
+          errors.push(ValidationError::Scope { name: d.name(), scoped: name });
+        }
+      }
+    }
+    errors
+  }
+
+  /// Provided types with several active candidates, and dependencies with none.
+  fn _check_candidates(&self, active: &[Registration], inactive: &[Registration]) -> Vec<ValidationError> {
+    let mut candidates = HashMap::<TypeId, Vec<&dyn IDepData>>::new();
+    for r in active {
+      candidates.entry(r.type_id()).or_default().push(r.dep_data());
+    }
+
+    let mut errors = vec![];
+    let mut reported = HashSet::new();
+    for r in active {
+      let ds = &candidates[&r.type_id()];
+      if ds.len() > 1 && reported.insert(r.type_id()) {
+        errors.push(ValidationError::Duplicate {
+          name: ds[0].name(),
+          locations: ds.iter().map(|d| d.location()).collect(),
+        });
+      }
+    }
+
+    let provided = |t: &TypeId| candidates.contains_key(t) || self.deps.0.contains_key(t) || self.bound.contains(t);
+    // `Factory<T>` creates new values with `T`'s provider, so a bound `T` does not do
+    let bindings = self.bindings.iter().map(Registration::type_id).collect::<HashSet<_>>();
+    let creatable = |t: &TypeId| candidates.contains_key(t) && !bindings.contains(t);
+    for d in active.iter().map(Registration::dep_data) {
+      let children = d.children().iter().zip(d.children_names());
+      for (c, name) in children.chain(d.deferred().iter().zip(d.deferred_names())) {
+        let t = c();
+        let found = if d.factories().iter().any(|f| f() == t) {
+          creatable(&t)
+        } else {
+          // `Arc<T>` and `Lazy<T>` dependencies may be bound as `Arc<T>`, see `Deps::get_arc`
+          provided(&t) || d.shared().iter().any(|(s, arc)| s() == t && provided(&arc()))
+        };
+        if found {
+          continue;
+        }
+
+        let locations = inactive
+          .iter()
+          .filter(|r| r.type_id() == t)
+          .map(|r| r.dep_data().location())
+          .collect::<Vec<_>>();
+        errors.push(if locations.is_empty() {
+          ValidationError::Missing { name, needed_by: d.name() }
+        } else {
+          ValidationError::Inactive {
+            name,
+            needed_by: d.name(),
+            profile: self.profile.clone(),
+            locations,
+          }
+        });
+      }
+    }
+    errors
+  }
+
+  fn _sync_dep_data(&self) -> Vec<DepData> {
+    let dep_data = self
+      ._registrations()
+      .iter()
+      .filter(|r| r.dep_data().scoped() == self.scope.is_some())
+      .filter_map(|r| match r {
+        Registration::Sync(d) => Some(d.clone()),
+        Registration::Async(_) => None,
+      })
+      .collect::<Vec<_>>();
+
+    GraphSorter::sort(dep_data)
+  }
+
+  fn _async_dep_data(&self) -> Vec<ADepData> {
+    let dep_data = self
+      ._registrations()
+      .iter()
+      .filter(|r| r.dep_data().scoped() == self.scope.is_some())
+      .filter_map(|r| match r {
+        Registration::Sync(_) => None,
+        Registration::Async(d) => Some(d.clone()),
+      })
       .collect::<Vec<_>>();
 
-    let dep_data = GraphSorter::sort(dep_data);
+    GraphSorter::sort(dep_data)
+  }
+
+  /// Types of providers which are overridden, unused, or whose every dependent
+  /// is skipped, unless they are roots.
+  ///
+  /// `sorted` must list dependencies before their dependents.
+  fn _skipped(&self, sorted: &[&dyn IDepData]) -> HashSet<TypeId> {
+    let mut parents = HashMap::<TypeId, Vec<TypeId>>::new();
+    for n in sorted {
+      for c in n.children().iter().chain(n.deferred()) {
+        parents.entry(c()).or_default().push(n.type_id());
+      }
+    }
+
+    let mut skipped = self._skipped_unused(self._registrations());
+    for n in sorted.iter().rev() {
+      let t = n.type_id();
+      let overridden = self.overridden.contains(&t);
+      // roots are used even if every provider depending on them is not
+      let unused = !self.roots.contains(&t)
+        && parents
+          .get(&t)
+          .is_some_and(|ps| ps.iter().all(|p| skipped.contains(p)));
+
+      if overridden || unused {
+        skipped.insert(t);
+      }
+    }
+
+    skipped
+  }
 
+  fn _build_sync(&mut self, dep_data: Vec<DepData>, skipped: &HashSet<TypeId>) {
     for dep in dep_data {
       let dep_type = (dep.type_id)();
+      if skipped.contains(&dep_type) {
+        continue;
+      }
+      let initialized_dep = (dep.initializer)(&self.deps);
+      self.deps.0.insert(dep_type, initialized_dep);
+    }
+  }
+
+  async fn _build_async(&mut self, dep_data: Vec<ADepData>, skipped: &HashSet<TypeId>) {
+    for dep in dep_data {
+      let dep_type = (dep.type_id)();
+      if skipped.contains(&dep_type) {
+        continue;
+      }
       let initialized_dep = (dep.initializer)(&self.deps).await;
       self.deps.0.insert(dep_type, initialized_dep);
     }
   }
 
+  /// Lets `Factory` dependencies construct the types of `sync`.
+  fn _insert_handle(&mut self, sync: &[DepData]) {
+    Handle::insert(&mut self.deps, sync.iter().map(|d| ((d.type_id)(), d.initializer)));
+  }
+
   fn _get_deps(self) -> Deps {
     self.deps
   }
 
   pub fn build(mut self) -> Deps {
-    self._build_sync();
+    let sync = self._sync_dep_data();
+    let sorted = sync.iter().map(|d| d as &dyn IDepData).collect::<Vec<_>>();
+    let skipped = self._skipped(&sorted);
+    self._insert_handle(&sync);
+
+    self._build_sync(sync, &skipped);
     self._get_deps()
   }
 
   pub async fn async_build(mut self) -> Deps {
-    self._build_sync();
-    self._build_async().await;
+    let sync = self._sync_dep_data();
+    let async_ = self._async_dep_data();
+    let skipped = {
+      // async providers may depend on sync ones, never the other way around
+      let sorted = sync
+        .iter()
+        .map(|d| d as &dyn IDepData)
+        .chain(async_.iter().map(|d| d as &dyn IDepData))
+        .collect::<Vec<_>>();
+      self._skipped(&sorted)
+    };
+    self._insert_handle(&sync);
+
+    self._build_sync(sync, &skipped);
+    self._build_async(async_, &skipped).await;
     self._get_deps()
   }
 }
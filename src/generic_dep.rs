@@ -0,0 +1,41 @@
+use std::marker::PhantomData;
+
+use crate::Registration;
+
+/// Implemented by `#[autowired]` generic providers for every instantiation
+/// satisfying their bounds.
+pub trait GenericDep {
+  const REGISTRATION: Registration;
+}
+
+/// Resolves the registration of a generic provider's instantiation from a
+/// concrete type, via autoref specialization: `(&Probe::<T>::new()).registration()`
+/// is `Some` if `T: GenericDep`, `None` otherwise.
+pub struct Probe<T>(PhantomData<T>);
+
+impl<T> Probe<T> {
+  #[allow(clippy::new_without_default)]
+  pub fn new() -> Self {
+    Self(PhantomData)
+  }
+}
+
+pub trait ProbeGeneric {
+  fn registration(&self) -> Option<Registration>;
+}
+
+impl<T: GenericDep> ProbeGeneric for Probe<T> {
+  fn registration(&self) -> Option<Registration> {
+    Some(T::REGISTRATION)
+  }
+}
+
+pub trait ProbeConcrete {
+  fn registration(&self) -> Option<Registration>;
+}
+
+impl<T> ProbeConcrete for &Probe<T> {
+  fn registration(&self) -> Option<Registration> {
+    None
+  }
+}
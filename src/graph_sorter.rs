@@ -1,84 +1,115 @@
-use std::collections::HashMap;
+use std::{any::TypeId, collections::HashMap};
 
-use crate::IDepData;
+use crate::{IDepData, ValidationError};
 
 pub struct GraphSorter;
 
 impl GraphSorter {
+  /// Orders providers so that each one comes after all of its children.
+  ///
+  /// The order is stable: nodes are visited by provider name, then by source
+  /// location, so independent providers always come out in the same order.
+  /// Apart from that initial ordering, sorting is O(V + E).
+  ///
+  /// Panics if the providers depend on each other in a cycle, see [`GraphSorter::try_sort`].
   pub fn sort<DP: IDepData>(dep_datas: Vec<DP>) -> Vec<DP> {
-    let nodes = dep_datas;
-    let mut sorted = Vec::<DP>::new();
-    let mut marks = HashMap::<&DP, Mark>::new();
+    Self::try_sort(dep_datas).unwrap_or_else(|cycle| panic!("{}", ValidationError::Cycle { cycle }))
+  }
+
+  /// Like [`GraphSorter::sort`], or the names of the providers in a cycle,
+  /// each depending on the next one and the last one on the first one.
+  pub fn try_sort<DP: IDepData>(dep_datas: Vec<DP>) -> Result<Vec<DP>, Vec<&'static str>> {
+    let mut nodes = dep_datas;
+    nodes.sort_by_key(|n| (n.name(), n.location()));
 
-    for n in nodes.iter() {
-      marks.insert(n, Mark::None);
+    let mut index = HashMap::<TypeId, Vec<usize>>::new();
+    for (i, n) in nodes.iter().enumerate() {
+      index.entry(n.type_id()).or_default().push(i);
     }
 
+    let children = nodes
+      .iter()
+      .map(|n| {
+        n.children()
+          .iter()
+          .filter_map(|c| index.get(&c()))
+          .flatten()
+          .copied()
+          .collect()
+      })
+      .collect::<Vec<Vec<usize>>>();
+
     let mut sorter = Sorter {
-      nodes: &nodes,
-      sorted: &mut sorted,
-      marks: &mut marks,
+      children: &children,
+      sorted: Vec::with_capacity(nodes.len()),
+      marks: vec![Mark::None; nodes.len()],
     };
 
-    sorter.sort().unwrap();
+    if let Err(cycle) = sorter.sort() {
+      return Err(cycle.into_iter().map(|i| nodes[i].name()).collect());
+    }
 
-    sorted
+    let mut nodes = nodes.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(
+      sorter
+        .sorted
+        .into_iter()
+        .map(|i| nodes[i].take().unwrap())
+        .collect(),
+    )
   }
 }
 
+#[derive(Clone, Copy)]
 enum Mark {
   None,
   Temp,
   Permanent,
 }
 
-struct Sorter<'a, DP: IDepData> {
-  nodes: &'a Vec<DP>,
-  sorted: &'a mut Vec<DP>,
-  marks: &'a mut HashMap<&'a DP, Mark>,
+struct Sorter<'a> {
+  children: &'a [Vec<usize>],
+  sorted: Vec<usize>,
+  marks: Vec<Mark>,
 }
 
-impl<'a, DP: IDepData> Sorter<'a, DP> {
-  pub fn sort(&mut self) -> Result<(), ()> {
-    loop {
-      if !self
-        .marks
-        .values()
-        .any(|m| matches!(m, Mark::Temp | Mark::None))
-      {
-        return Ok(());
-      }
-
-      let (&d, _) = self
-        .marks
-        .iter()
-        .find(|(_, m)| matches!(m, Mark::None))
-        .unwrap();
-      self.visit(d)?;
+impl<'a> Sorter<'a> {
+  /// Fails with the nodes of a cycle.
+  pub fn sort(&mut self) -> Result<(), Vec<usize>> {
+    for n in 0..self.children.len() {
+      self.visit(n)?;
     }
-  }
 
-  pub fn visit(&mut self, n: &'a DP) -> Result<(), ()> {
-    match self.marks.get(n).unwrap_or(&Mark::None) {
-      Mark::Permanent => return Ok(()),
-      Mark::Temp => return Err(()),
-      Mark::None => {}
-    };
+    Ok(())
+  }
 
-    self.marks.insert(n, Mark::Temp);
+  pub fn visit(&mut self, n: usize) -> Result<(), Vec<usize>> {
+    // explicit stack of (node, next child), long dependency chains would
+    // overflow the call stack
+    let mut stack = vec![(n, 0)];
+
+    while let Some((n, i)) = stack.pop() {
+      if i == 0 {
+        match self.marks[n] {
+          Mark::Permanent => continue,
+          // the stack holds the path from `n`'s first visit
+          Mark::Temp => {
+            let start = stack.iter().position(|(m, _)| *m == n).unwrap_or(0);
+            return Err(stack[start..].iter().map(|(m, _)| *m).collect());
+          }
+          Mark::None => self.marks[n] = Mark::Temp,
+        }
+      }
 
-    for m in self
-      .nodes
-      .iter()
-      .filter(|m| n.children().contains(&m.name()))
-    {
-      self.visit(m)?
+      if let Some(&m) = self.children[n].get(i) {
+        stack.push((n, i + 1));
+        stack.push((m, 0));
+      } else {
+        self.marks[n] = Mark::Permanent;
+        self.sorted.push(n);
+      }
     }
 
-    self.marks.insert(n, Mark::Permanent);
-
-    self.sorted.push(n.clone());
-
     Ok(())
   }
 }
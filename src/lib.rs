@@ -1,26 +1,82 @@
-use std::{any::Any, collections::HashMap, hash::Hash};
+use std::{any::Any, collections::HashMap, sync::Arc};
 
-pub use std::{any::TypeId, future::Future, pin::Pin};
+pub use std::{any::TypeId, future::Future, panic::Location, pin::Pin};
 
+#[cfg(feature = "tokio")]
+pub use crate::current::{current, try_current, CurrentError};
+pub use crate::binding::Dependencies;
+pub use crate::deferred::{Factory, Lazy};
 pub use crate::deps::Deps;
 use crate::deps_builder::DepsBuilder;
-pub use crate::provider::{Context, Provider};
+pub use crate::provider::{Context, Provider, ProviderBuilder};
+pub use crate::validation::{ProviderInfo, ValidationError, ValidationReport};
 
 pub use async_trait::async_trait;
-pub use autowired_macros::{autowired, Context};
+pub use autowired_macros::{autowired, autowired_module, Context};
+#[cfg(feature = "config")]
+pub use autowired_macros::autowired_config;
 pub use const_format::{concatcp, formatcp};
 pub use impls::impls;
+#[cfg(feature = "inventory")]
 pub use inventory::submit;
+#[cfg(feature = "config")]
+#[doc(hidden)]
+pub use serde;
 
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
+mod binding;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "tokio")]
+mod current;
+mod deferred;
 mod deps;
 mod deps_builder;
+mod generic_dep;
 mod graph_sorter;
 mod provider;
+mod validation;
 
-pub trait Dep<T>: Clone {}
+#[doc(hidden)]
+pub use crate::generic_dep::{GenericDep, Probe, ProbeConcrete, ProbeGeneric};
+#[doc(hidden)]
+pub use crate::graph_sorter::GraphSorter;
+
+/// Implemented by every type context `C` can provide, either directly (`P = ()`)
+/// or through the contexts it flattens or imports (`P` is the path to the binding).
+pub trait Dep<C, P = ()> {}
 pub trait SharedDep<T>: Dep<T> {}
-pub trait AutowiredDep: Clone {}
-pub trait AsyncAutowiredDep: Clone {}
+
+impl<T, C: Provides<T, P>, P> Dep<C, P> for T {}
+
+/// Context bindings, implemented by `#[autowired]` and `#[derive(Context)]`.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(message = "`{T}` cannot be autowired in context `{Self}`")]
+pub trait Provides<T, P = ()> {}
+
+/// Path of an `Arc<T>` dependency on a provided `T`, which is shared rather than cloned.
+#[doc(hidden)]
+pub struct Shared;
+
+impl<C: Provides<T, P>, T, P> Provides<Arc<T>, (Shared, P)> for C {}
+
+/// Path of a `Lazy<T>` or `Factory<T>` dependency on a provided `T`, which is resolved later.
+#[doc(hidden)]
+pub struct Deferred;
+
+impl<C: Provides<T, P>, T, P> Provides<Lazy<T>, (Deferred, P)> for C {}
+// only providers can create new values, unlike context fields and runtime bindings
+impl<C: Provides<T, P>, T: AutowiredDep, P> Provides<Factory<T>, (Deferred, P)> for C {}
+
+/// Fails to compile unless context `C` provides `T`, see `#[autowired]`'s dependency checks.
+#[doc(hidden)]
+pub fn provided<C: Provides<T, P>, T, P>() {}
+
+pub trait AutowiredDep {}
+pub trait AsyncAutowiredDep {}
 /*
 pub trait Dep<T>: Clone {}
 pub trait SharedDep<T>: Dep<T> {}
@@ -29,33 +85,170 @@ pub trait AsyncAutowiredDep<T>: AutowiredDep<T> {}
  */
 
 // pub type DependencyValue = Box<dyn Any>;
-pub type DependencyValue = Box<dyn Any + Send + Sync>;
+/// A provided `T`, stored as an `Arc<T>` so that it can be shared without cloning.
+pub type DependencyValue = Arc<dyn Any + Send + Sync>;
 pub type DependencyMap = HashMap<TypeId, DependencyValue>;
+/// The `TypeId`s of `T` and `Arc<T>`, for a dependency which either provides.
+pub type SharedTypeIds = (fn() -> TypeId, fn() -> TypeId);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct DepData {
   pub name: &'static str,
-  pub children: &'static [&'static str],
+  pub children: &'static [fn() -> TypeId],
+  pub children_names: &'static [&'static str],
+  /// `Lazy` and `Factory` dependencies, which are resolved after this provider is built.
+  pub deferred: &'static [fn() -> TypeId],
+  pub deferred_names: &'static [&'static str],
+  /// Targets of the `Factory` dependencies among `deferred`, which only providers can create.
+  pub factories: &'static [fn() -> TypeId],
+  /// `T` and `Arc<T>` for the `Arc<T>` and `Lazy<T>` dependencies, which a
+  /// bound `Arc<T>` provides too, see [`Deps::get_arc`].
+  pub shared: &'static [SharedTypeIds],
   pub type_id: fn() -> TypeId,
+  /// Generic providers instantiated by this provider's children.
+  pub instantiations: fn() -> Vec<Registration>,
+  pub location: &'static Location<'static>,
+  pub condition: Condition,
+  /// Built by [`Provider::child`] rather than with the provider, see `#[autowired(scoped)]`.
+  pub scoped: bool,
   pub initializer: fn(&Deps) -> DependencyValue,
 }
 
-inventory::collect!(DepData);
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct ADepData {
   pub name: &'static str,
-  pub children: &'static [&'static str],
+  pub children: &'static [fn() -> TypeId],
+  pub children_names: &'static [&'static str],
+  pub deferred: &'static [fn() -> TypeId],
+  pub deferred_names: &'static [&'static str],
+  pub factories: &'static [fn() -> TypeId],
+  pub shared: &'static [SharedTypeIds],
   pub type_id: fn() -> TypeId,
+  pub instantiations: fn() -> Vec<Registration>,
+  pub location: &'static Location<'static>,
+  pub condition: Condition,
+  pub scoped: bool,
   // pub initializer: fn(&Deps) -> Pin<Box<dyn Future<Output = Box<dyn Any>>>>,
   pub initializer: fn(&Deps) -> Pin<Box<dyn Future<Output = DependencyValue> + Send + '_>>,
 }
 
-inventory::collect!(ADepData);
+/// When a provider is a candidate for its type, see `#[autowired(profile = .., when = ..)]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Condition {
+  /// The profile which must be active, see [`Context::profile`].
+  pub profile: Option<&'static str>,
+  pub when: Option<fn() -> bool>,
+}
+
+impl Condition {
+  pub fn is_conditional(&self) -> bool {
+    self.profile.is_some() || self.when.is_some()
+  }
+
+  pub fn is_met(&self, profile: Option<&str>) -> bool {
+    self.profile.is_none_or(|p| Some(p) == profile) && self.when.is_none_or(|f| f())
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum Registration {
+  Sync(DepData),
+  Async(ADepData),
+}
+
+impl Registration {
+  pub fn type_id(&self) -> TypeId {
+    match self {
+      Registration::Sync(d) => (d.type_id)(),
+      Registration::Async(d) => (d.type_id)(),
+    }
+  }
+
+  pub fn instantiations(&self) -> Vec<Registration> {
+    match self {
+      Registration::Sync(d) => (d.instantiations)(),
+      Registration::Async(d) => (d.instantiations)(),
+    }
+  }
 
-trait IDepData: Eq + Hash + Clone {
+  pub fn dep_data(&self) -> &dyn IDepData {
+    match self {
+      Registration::Sync(d) => d,
+      Registration::Async(d) => d,
+    }
+  }
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(Registration);
+
+/// Providers registered explicitly with [`autowired_module!`], for targets
+/// where `inventory` is unavailable.
+#[derive(Debug)]
+pub struct Module {
+  pub name: &'static str,
+  pub registrations: &'static [Registration],
+}
+
+#[doc(hidden)]
+pub trait IDepData {
   fn name(&self) -> &'static str;
-  fn children(&self) -> &'static [&'static str];
+  fn children(&self) -> &'static [fn() -> TypeId];
+  fn children_names(&self) -> &'static [&'static str];
+  fn deferred(&self) -> &'static [fn() -> TypeId];
+  fn deferred_names(&self) -> &'static [&'static str];
+  fn factories(&self) -> &'static [fn() -> TypeId];
+  fn shared(&self) -> &'static [SharedTypeIds];
+  fn type_id(&self) -> TypeId;
+  fn location(&self) -> &'static Location<'static>;
+  fn condition(&self) -> Condition;
+  fn scoped(&self) -> bool;
+}
+
+impl IDepData for Registration {
+  fn name(&self) -> &'static str {
+    self.dep_data().name()
+  }
+
+  fn children(&self) -> &'static [fn() -> TypeId] {
+    self.dep_data().children()
+  }
+
+  fn children_names(&self) -> &'static [&'static str] {
+    self.dep_data().children_names()
+  }
+
+  fn deferred(&self) -> &'static [fn() -> TypeId] {
+    self.dep_data().deferred()
+  }
+
+  fn deferred_names(&self) -> &'static [&'static str] {
+    self.dep_data().deferred_names()
+  }
+
+  fn factories(&self) -> &'static [fn() -> TypeId] {
+    self.dep_data().factories()
+  }
+
+  fn shared(&self) -> &'static [SharedTypeIds] {
+    self.dep_data().shared()
+  }
+
+  fn type_id(&self) -> TypeId {
+    self.dep_data().type_id()
+  }
+
+  fn location(&self) -> &'static Location<'static> {
+    self.dep_data().location()
+  }
+
+  fn condition(&self) -> Condition {
+    self.dep_data().condition()
+  }
+
+  fn scoped(&self) -> bool {
+    self.dep_data().scoped()
+  }
 }
 
 impl IDepData for DepData {
@@ -63,9 +256,45 @@ impl IDepData for DepData {
     self.name
   }
 
-  fn children(&self) -> &'static [&'static str] {
+  fn children(&self) -> &'static [fn() -> TypeId] {
     self.children
   }
+
+  fn children_names(&self) -> &'static [&'static str] {
+    self.children_names
+  }
+
+  fn deferred(&self) -> &'static [fn() -> TypeId] {
+    self.deferred
+  }
+
+  fn deferred_names(&self) -> &'static [&'static str] {
+    self.deferred_names
+  }
+
+  fn factories(&self) -> &'static [fn() -> TypeId] {
+    self.factories
+  }
+
+  fn shared(&self) -> &'static [SharedTypeIds] {
+    self.shared
+  }
+
+  fn type_id(&self) -> TypeId {
+    (self.type_id)()
+  }
+
+  fn location(&self) -> &'static Location<'static> {
+    self.location
+  }
+
+  fn condition(&self) -> Condition {
+    self.condition
+  }
+
+  fn scoped(&self) -> bool {
+    self.scoped
+  }
 }
 
 impl IDepData for ADepData {
@@ -73,7 +302,43 @@ impl IDepData for ADepData {
     self.name
   }
 
-  fn children(&self) -> &'static [&'static str] {
+  fn children(&self) -> &'static [fn() -> TypeId] {
     self.children
   }
+
+  fn children_names(&self) -> &'static [&'static str] {
+    self.children_names
+  }
+
+  fn deferred(&self) -> &'static [fn() -> TypeId] {
+    self.deferred
+  }
+
+  fn deferred_names(&self) -> &'static [&'static str] {
+    self.deferred_names
+  }
+
+  fn factories(&self) -> &'static [fn() -> TypeId] {
+    self.factories
+  }
+
+  fn shared(&self) -> &'static [SharedTypeIds] {
+    self.shared
+  }
+
+  fn type_id(&self) -> TypeId {
+    (self.type_id)()
+  }
+
+  fn location(&self) -> &'static Location<'static> {
+    self.location
+  }
+
+  fn condition(&self) -> Condition {
+    self.condition
+  }
+
+  fn scoped(&self) -> bool {
+    self.scoped
+  }
 }
@@ -1,39 +1,289 @@
-use std::marker::PhantomData;
+use std::{
+  any::{type_name, TypeId},
+  future::Future,
+  marker::PhantomData,
+  panic::Location,
+  sync::Arc,
+};
 
 use async_trait::async_trait;
 
-use crate::{Dep, Deps, DepsBuilder};
+use crate::{
+  binding::{self, AsyncBinding, Binding, Dependencies},
+  deferred::Handle,
+  Deps, DepsBuilder, Module, ProviderInfo, Registration, ValidationError, ValidationReport,
+};
 
 #[async_trait]
 pub trait Context {
   fn get_initial_deps(&self) -> Deps;
 
+  /// Modules whose providers are registered, used when the `inventory`
+  /// feature is disabled. Includes the modules of imported contexts.
+  fn modules(&self) -> Vec<&'static Module> {
+    vec![]
+  }
+
+  /// The active profile, selecting which `#[autowired(profile = ..)]` providers are built.
+  fn profile(&self) -> Option<&str> {
+    None
+  }
+
+  /// Types bound outside of providers, by `#[scoped(..)]` seeds or
+  /// `#[bound(..)]` runtime bindings.
+  fn bound_types(&self) -> Vec<TypeId> {
+    vec![]
+  }
+
+  /// Entry points of the application: providers which none of them depends
+  /// on, even transitively, are reported unused by [`Context::validate`].
+  fn roots(&self) -> Vec<TypeId> {
+    vec![]
+  }
+
+  /// Whether the providers which are unused by the [`Context::roots`] are
+  /// left unbuilt, see `#[skip_unused]`.
+  fn skip_unused(&self) -> bool {
+    false
+  }
+
+  /// Generic provider instantiations to build even if no other provider
+  /// depends on them, e.g. because they are only used with [`Provider::provide`].
+  fn instantiations(&self) -> Vec<Registration> {
+    vec![]
+  }
+
+  /// Builds every registered provider, dependencies first.
+  ///
+  /// The initialization order is deterministic: independent providers are
+  /// initialized by name, then by source location.
   fn get_provider(&self) -> Provider<Self> {
-    let builder = DepsBuilder::new(self.get_initial_deps());
+    let builder = deps_builder(self);
+    let scoped = builder.scoped();
+    let deps = builder.build();
+    Provider::new(deps, scoped.into())
+  }
+
+  async fn get_async_provider(&self) -> Provider<Self> {
+    let builder = deps_builder(self);
+    let scoped = builder.scoped();
+    let deps = builder.async_build().await;
+    Provider::new(deps, scoped.into())
+  }
+
+  /// Builds only the providers of `D`, a tuple of types such as `(Migrate,)`,
+  /// and their dependencies, e.g. for a CLI subcommand.
+  fn get_provider_for<D: Dependencies>(&self) -> Provider<Self> {
+    let builder = deps_builder(self).with_targets(D::TYPE_IDS.iter().map(|t| t()).collect());
+    let scoped = builder.scoped();
     let deps = builder.build();
+    Provider::new(deps, scoped.into())
+  }
+
+  async fn get_async_provider_for<D: Dependencies>(&self) -> Provider<Self> {
+    let builder = deps_builder(self).with_targets(D::TYPE_IDS.iter().map(|t| t()).collect());
+    let scoped = builder.scoped();
+    let deps = builder.async_build().await;
+    Provider::new(deps, scoped.into())
+  }
+
+  /// Checks that every registered provider can be built, without building
+  /// any: no provider's initializer is called.
+  fn validate(&self) -> Result<ValidationReport, Vec<ValidationError>> {
+    deps_builder(self).with_bound(self.bound_types()).validate()
+  }
+
+  fn provider_builder(&self) -> ProviderBuilder<'_, Self>
+  where
+    Self: Sized,
+  {
+    ProviderBuilder {
+      ctx: self,
+      overrides: Deps::default(),
+      overridden: Vec::new(),
+      bindings: Vec::new(),
+      bound: Deps::default(),
+    }
+  }
+}
+
+fn deps_builder<C: Context + ?Sized>(ctx: &C) -> DepsBuilder {
+  let builder = DepsBuilder::new(ctx.get_initial_deps())
+    .with_instantiations(ctx.instantiations())
+    .with_profile(ctx.profile())
+    .with_roots(ctx.roots(), ctx.skip_unused());
+  #[cfg(not(feature = "inventory"))]
+  let builder = builder.with_modules(ctx.modules());
+  builder
+}
+
+/// The built dependencies of context `C`; cloning it is cheap and shares them.
+pub struct Provider<C: Context + ?Sized> {
+  deps: Arc<Deps>,
+  /// Providers built by [`Provider::child`].
+  scoped: Arc<[Registration]>,
+  _pd: PhantomData<fn() -> C>,
+}
+
+impl<C: Context + ?Sized> Clone for Provider<C> {
+  fn clone(&self) -> Self {
     Provider {
-      deps,
+      deps: self.deps.clone(),
+      scoped: self.scoped.clone(),
       _pd: PhantomData,
     }
   }
+}
 
-  async fn get_async_provider(&self) -> Provider<Self> {
-    let builder = DepsBuilder::new(self.get_initial_deps());
-    let deps = builder.async_build().await;
+impl<C: Context + ?Sized> Provider<C> {
+  fn new(deps: Deps, scoped: Arc<[Registration]>) -> Self {
+    let deps = Arc::new(deps);
+    Handle::bind(&deps);
     Provider {
       deps,
+      scoped,
       _pd: PhantomData,
     }
   }
-}
 
-pub struct Provider<C: Context + ?Sized> {
-  deps: Deps,
-  _pd: PhantomData<C>,
+  #[cfg(any(feature = "actix", feature = "tokio"))]
+  pub(crate) fn deps(&self) -> &Arc<Deps> {
+    &self.deps
+  }
+
+  fn child_builder(&self, seeds: Deps) -> DepsBuilder {
+    DepsBuilder::new(Deps(self.deps.0.clone()))
+      .with_overrides(seeds)
+      .with_scope(self.scoped.to_vec())
+  }
+
+  /// A child scope, e.g. of a request, sharing this provider's dependencies
+  /// and building its own `#[autowired(scoped)]` providers.
+  ///
+  /// `seeds` are bound in the child only, e.g. the request's user; their
+  /// types are declared with `#[scoped(..)]` on the context.
+  pub fn child(&self, seeds: Deps) -> Provider<C> {
+    let deps = self.child_builder(seeds).build();
+    Provider::new(deps, self.scoped.clone())
+  }
+
+  pub async fn async_child(&self, seeds: Deps) -> Provider<C> {
+    let deps = self.child_builder(seeds).async_build().await;
+    Provider::new(deps, self.scoped.clone())
+  }
 }
 
 impl<C: Context> Provider<C> {
+  /// A clone of the provided `T`, see [`Provider::get`] to borrow it instead.
   pub fn provide<T: Clone + 'static>(&self) -> T {
     self.deps.get()
   }
+
+  /// Borrows the provided `T`, which does not need to be `Clone`.
+  pub fn get<T: 'static>(&self) -> &T {
+    self.deps.get_ref()
+  }
+
+  /// The shared `T`, which does not need to be `Clone`.
+  pub fn provide_arc<T: Send + Sync + 'static>(&self) -> Arc<T> {
+    self.deps.get_arc()
+  }
+
+  pub fn provide_named<T: Clone + 'static>(&self, name: &str) -> T {
+    self.deps.get_named(name)
+  }
+}
+
+/// Builds a [`Provider`] with some of its dependencies replaced, e.g. by mocks in tests,
+/// or bound at runtime rather than by `#[autowired]`.
+pub struct ProviderBuilder<'a, C: Context> {
+  ctx: &'a C,
+  overrides: Deps,
+  /// The overridden types, checked once the context's providers are known.
+  overridden: Vec<(TypeId, ProviderInfo)>,
+  bindings: Vec<Registration>,
+  /// How the bindings are built, see [`crate::binding::Binding`].
+  bound: Deps,
+}
+
+impl<'a, C: Context> ProviderBuilder<'a, C> {
+  /// Provides `value` as `T`, skipping `T`'s registered initializer and every
+  /// provider which was only needed to initialize `T`.
+  ///
+  /// Building the provider panics unless the context provides `T`.
+  #[track_caller]
+  pub fn override_with<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+    let info = ProviderInfo { name: type_name::<T>(), location: Location::caller() };
+    self.overridden.push((TypeId::of::<T>(), info));
+    self.overrides.0.insert(TypeId::of::<T>(), Arc::new(value));
+    self
+  }
+
+  /// Provides `value` as `T`, like a provider without dependencies.
+  ///
+  /// Runtime bindings are checked along with the registered providers, so a
+  /// type cannot be both bound and registered. Declare the bound types which
+  /// `#[autowired]` providers depend on with `#[bound(..)]` on the context.
+  #[track_caller]
+  pub fn bind<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+    self.bindings.push(binding::registration::<T, ()>(Location::caller()));
+    self.bound.insert(Binding::<T>::Value(Arc::new(value)));
+    self
+  }
+
+  /// Provides `T` as built by `f`, once its dependencies `D` are built, e.g.
+  /// `bind_fn::<Service, (Db,), _>(|deps| Service(deps.get()))`.
+  #[track_caller]
+  pub fn bind_fn<T, D, F>(mut self, f: F) -> Self
+  where
+    T: Send + Sync + 'static,
+    D: Dependencies,
+    F: Fn(&Deps) -> T + Send + Sync + 'static,
+  {
+    self.bindings.push(binding::registration::<T, D>(Location::caller()));
+    self.bound.insert(Binding::<T>::Fn(Box::new(f)));
+    self
+  }
+
+  /// Like [`ProviderBuilder::bind_fn`], built by [`ProviderBuilder::async_build`] only.
+  #[track_caller]
+  pub fn bind_async<T, D, F, Fut>(mut self, f: F) -> Self
+  where
+    T: Send + Sync + 'static,
+    D: Dependencies,
+    F: Fn(&Deps) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+  {
+    self.bindings.push(binding::async_registration::<T, D>(Location::caller()));
+    self.bound.insert(AsyncBinding::<T>(Box::new(move |deps| Box::pin(f(deps)))));
+    self
+  }
+
+  fn deps_builder(self) -> DepsBuilder {
+    let builder = deps_builder(self.ctx).with_bindings(self.bindings, self.bound);
+    for (t, info) in &self.overridden {
+      assert!(
+        builder.provides(*t),
+        "`{}` is overridden at {}, but context `{}` does not provide it",
+        info.name,
+        info.location,
+        type_name::<C>()
+      );
+    }
+    builder.with_overrides(self.overrides)
+  }
+
+  pub fn build(self) -> Provider<C> {
+    let builder = self.deps_builder();
+    let scoped = builder.scoped();
+    let deps = builder.build();
+    Provider::new(deps, scoped.into())
+  }
+
+  pub async fn async_build(self) -> Provider<C> {
+    let builder = self.deps_builder();
+    let scoped = builder.scoped();
+    let deps = builder.async_build().await;
+    Provider::new(deps, scoped.into())
+  }
 }
@@ -0,0 +1,74 @@
+use std::{
+  fmt::{self, Display},
+  panic::Location,
+};
+
+/// A provider, by its provided type's name and its source location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderInfo {
+  pub name: &'static str,
+  pub location: &'static Location<'static>,
+}
+
+/// The providers a context would build, see [`crate::Context::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+  /// Built with the provider, in a valid initialization order.
+  pub providers: Vec<ProviderInfo>,
+  /// Built per child scope, see `#[autowired(scoped)]`.
+  pub scoped: Vec<ProviderInfo>,
+  /// Not built, as their condition is not met.
+  pub inactive: Vec<ProviderInfo>,
+  /// Not needed by any of the context's roots, see [`crate::Context::roots`].
+  pub unused: Vec<ProviderInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+  /// Several active providers of one type.
+  Duplicate { name: &'static str, locations: Vec<&'static Location<'static>> },
+  /// A dependency whose providers are all inactive for `profile`.
+  Inactive {
+    name: &'static str,
+    needed_by: &'static str,
+    profile: Option<String>,
+    locations: Vec<&'static Location<'static>>,
+  },
+  /// A dependency which has no provider and is not bound by the context.
+  Missing { name: &'static str, needed_by: &'static str },
+  /// An unscoped provider depending on a scoped one.
+  Scope { name: &'static str, scoped: &'static str },
+  /// Providers depending on each other, each on the next one and the last one on the first one.
+  Cycle { cycle: Vec<&'static str> },
+}
+
+fn join(locations: &[&Location<'_>]) -> String {
+  locations.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+impl Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Duplicate { name, locations } => write!(
+        f,
+        "{} providers of `{}` are active, at {}",
+        locations.len(),
+        name,
+        join(locations)
+      ),
+      Self::Inactive { name, needed_by, profile, locations } => write!(
+        f,
+        "no provider of `{}`, needed by `{}`, is active for profile {:?}, candidates are at {}",
+        name,
+        needed_by,
+        profile,
+        join(locations)
+      ),
+      Self::Missing { name, needed_by } => write!(f, "no provider of `{}`, needed by `{}`", name, needed_by),
+      Self::Scope { name, scoped } => write!(f, "`{}` depends on scoped `{}`, so it must be scoped too", name, scoped),
+      Self::Cycle { cycle } => write!(f, "dependency cycle: {} -> {}", cycle.join(" -> "), cycle[0]),
+    }
+  }
+}
+
+impl std::error::Error for ValidationError {}
@@ -1,3 +1,5 @@
+#![cfg(feature = "inventory")]
+
 use std::sync::Arc;
 
 use autowired::{Context, TypeId};
@@ -0,0 +1,75 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::Arc;
+
+use autowired::{autowired, Context};
+
+trait Clock: Send + Sync {
+  fn now(&self) -> u64;
+}
+
+#[derive(Clone)]
+struct FixedClock(u64);
+
+impl Clock for FixedClock {
+  fn now(&self) -> u64 {
+    self.0
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DbUrl(&'static str);
+
+#[derive(Debug, Clone, PartialEq)]
+struct PoolSize(u32);
+
+#[derive(Context)]
+struct DbConfig {
+  url: DbUrl,
+  pool_size: PoolSize,
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Db {
+  url: DbUrl,
+  pool_size: PoolSize,
+  #[name = "replica"]
+  replica: DbUrl,
+  clock: Arc<dyn Clock>,
+}
+
+struct NotADependency;
+
+#[derive(Context)]
+struct Ctx {
+  #[flatten]
+  db: DbConfig,
+  #[provide(as = dyn Clock)]
+  clock: FixedClock,
+  #[name = "replica"]
+  replica: DbUrl,
+  #[skip]
+  _runtime: NotADependency,
+}
+
+#[test]
+fn test_context_fields() {
+  let p = Ctx {
+    db: DbConfig {
+      url: DbUrl("postgres://primary"),
+      pool_size: PoolSize(8),
+    },
+    clock: FixedClock(42),
+    replica: DbUrl("postgres://replica"),
+    _runtime: NotADependency,
+  }
+  .get_provider();
+
+  let db = p.provide::<Db>();
+  assert_eq!(db.url, DbUrl("postgres://primary"));
+  assert_eq!(db.pool_size, PoolSize(8));
+  assert_eq!(db.replica, DbUrl("postgres://replica"));
+  assert_eq!(db.clock.now(), 42);
+  assert_eq!(p.provide_named::<DbUrl>("replica"), DbUrl("postgres://replica"));
+}
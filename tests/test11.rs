@@ -0,0 +1,91 @@
+use autowired::{autowired_module, Context, Dep};
+
+autowired_module!(
+  pub mod infra {
+    use autowired::autowired;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DbUrl(pub &'static str);
+
+    #[derive(Debug, Clone)]
+    #[autowired(ctx = super::InfraContext)]
+    pub struct Db {
+      pub url: DbUrl,
+    }
+  }
+);
+
+autowired_module!(
+  pub mod api {
+    use autowired::autowired;
+
+    use super::infra::Db;
+
+    #[derive(Debug, Clone)]
+    #[autowired(ctx = super::ApiContext)]
+    pub struct UserService {
+      pub db: Db,
+    }
+  }
+);
+
+autowired_module!(
+  pub mod worker {
+    use autowired::autowired;
+
+    use super::infra::Db;
+
+    #[derive(Debug, Clone)]
+    pub struct Jobs(pub Db);
+
+    #[autowired(ctx = super::WorkerContext)]
+    async fn jobs(db: Db) -> Jobs {
+      Jobs(db)
+    }
+  }
+);
+
+#[derive(Context)]
+#[modules(infra::MODULE)]
+struct InfraContext {
+  url: infra::DbUrl,
+}
+
+#[derive(Context)]
+#[modules(api::MODULE)]
+struct ApiContext {
+  #[import]
+  infra: InfraContext,
+}
+
+#[derive(Context)]
+#[modules(worker::MODULE)]
+struct WorkerContext {
+  #[import]
+  infra: InfraContext,
+}
+
+fn infra() -> InfraContext {
+  InfraContext {
+    url: infra::DbUrl("postgres://localhost"),
+  }
+}
+
+fn assert_dep<T: Dep<C, P>, C, P>() {}
+
+#[test]
+fn test_import() {
+  assert_dep::<infra::Db, ApiContext, _>();
+  assert_dep::<infra::DbUrl, ApiContext, _>();
+
+  let p = ApiContext { infra: infra() }.get_provider();
+
+  assert_eq!(p.provide::<api::UserService>().db.url, infra::DbUrl("postgres://localhost"));
+}
+
+#[tokio::test]
+async fn test_async_import() {
+  let p = WorkerContext { infra: infra() }.get_async_provider().await;
+
+  assert_eq!(p.provide::<worker::Jobs>().0.url, infra::DbUrl("postgres://localhost"));
+}
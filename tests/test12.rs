@@ -0,0 +1,106 @@
+#![cfg(feature = "config")]
+
+use autowired::{
+  autowired_module,
+  config::{Config, ConfigError, Format},
+  Context,
+};
+
+autowired_module!(
+  pub mod storage {
+    use autowired::{autowired, autowired_config};
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[autowired_config(ctx = super::Ctx, prefix = "database")]
+    pub struct DbConfig {
+      pub url: String,
+      pub pool_size: u32,
+    }
+
+    #[derive(Debug, Clone)]
+    #[autowired(ctx = super::Ctx)]
+    pub struct Db {
+      pub config: DbConfig,
+    }
+  }
+);
+
+use storage::DbConfig;
+
+#[derive(Context)]
+#[modules(storage::MODULE)]
+struct Ctx {
+  config: Config,
+}
+
+const TOML: &str = r#"
+[database]
+url = "postgres://localhost"
+pool_size = 8
+"#;
+
+#[test]
+fn test_config_provider() {
+  let path = std::env::temp_dir().join(format!("autowired_test12_{}.yaml", std::process::id()));
+  std::fs::write(&path, "database:\n  pool_size: 16\n").unwrap();
+
+  let config = Config::new()
+    .with_source("defaults", Format::Toml, TOML)
+    .unwrap()
+    .with_file(&path)
+    .unwrap()
+    .with_vars("APP", [("APP__DATABASE__URL".into(), "postgres://prod".into())]);
+  std::fs::remove_file(&path).unwrap();
+  let p = Ctx { config }.get_provider();
+
+  assert_eq!(
+    p.provide::<storage::Db>().config,
+    DbConfig {
+      url: "postgres://prod".into(),
+      pool_size: 16,
+    }
+  );
+}
+
+#[test]
+fn test_config_errors() {
+  let config = Config::new()
+    .with_source("defaults", Format::Json, r#"{ "database": { "url": "postgres://localhost" } }"#)
+    .unwrap();
+  let e = config.get::<DbConfig>("database").unwrap_err();
+  assert!(matches!(e, ConfigError::Invalid { ref path, .. } if path == "database.pool_size"), "{}", e);
+
+  let config = config.with_vars("APP", [("APP__DATABASE__POOL_SIZE".into(), "many".into())]);
+  let e = config.get::<DbConfig>("database").unwrap_err();
+  assert!(matches!(e, ConfigError::Invalid { ref path, .. } if path == "database.pool_size"), "{}", e);
+}
+
+#[derive(Debug, PartialEq, autowired::serde::Deserialize)]
+#[serde(crate = "autowired::serde")]
+struct Auth {
+  password: String,
+  token: String,
+  admin: bool,
+  retries: u8,
+}
+
+#[test]
+fn test_config_env_strings() {
+  let vars = [
+    ("APP__AUTH__PASSWORD", "12345"),
+    ("APP__AUTH__TOKEN", "1e3"),
+    ("APP__AUTH__ADMIN", "true"),
+    ("APP__AUTH__RETRIES", "3"),
+  ];
+  let config = Config::new().with_vars("APP", vars.map(|(k, v)| (k.into(), v.into())));
+
+  assert_eq!(
+    config.get::<Auth>("auth").unwrap(),
+    Auth {
+      password: "12345".into(),
+      token: "1e3".into(),
+      admin: true,
+      retries: 3,
+    }
+  );
+}
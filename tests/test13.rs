@@ -0,0 +1,101 @@
+#![cfg(feature = "inventory")]
+
+use std::cell::Cell;
+
+use autowired::{autowired, Context};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Cache(&'static str);
+
+#[autowired(ctx = Ctx, profile = "dev")]
+fn memory_cache() -> Cache {
+  Cache("memory")
+}
+
+#[autowired(ctx = Ctx, profile = "prod")]
+fn redis_cache() -> Cache {
+  Cache("redis")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Mailer(&'static str);
+
+thread_local! {
+  static SMTP_CHECKS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn smtp_configured() -> bool {
+  SMTP_CHECKS.set(SMTP_CHECKS.get() + 1);
+  true
+}
+
+#[autowired(ctx = Ctx, when = smtp_configured)]
+fn smtp_mailer() -> Mailer {
+  Mailer("smtp")
+}
+
+#[autowired(ctx = Ctx, profile = "qa")]
+fn fake_mailer() -> Mailer {
+  Mailer("fake")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Queue(&'static str);
+
+#[autowired(ctx = Ctx, profile = "dev")]
+async fn memory_queue() -> Queue {
+  Queue("memory")
+}
+
+#[autowired(ctx = Ctx, profile = "prod")]
+async fn sqs_queue() -> Queue {
+  Queue("sqs")
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Service {
+  cache: Cache,
+  mailer: Mailer,
+}
+
+#[derive(Context)]
+#[conditional(Cache, Mailer, async Queue)]
+struct Ctx {
+  #[profile]
+  profile: &'static str,
+}
+
+#[test]
+fn test_profiles() {
+  let dev = Ctx { profile: "dev" }.get_provider().provide::<Service>();
+  assert_eq!(dev.cache, Cache("memory"));
+  assert_eq!(dev.mailer, Mailer("smtp"));
+
+  let prod = Ctx { profile: "prod" }.get_provider().provide::<Service>();
+  assert_eq!(prod.cache, Cache("redis"));
+}
+
+#[tokio::test]
+async fn test_async_profiles() {
+  let provider = Ctx { profile: "prod" }.get_async_provider().await;
+  assert_eq!(provider.provide::<Queue>(), Queue("sqs"));
+}
+
+#[test]
+fn test_condition_checked_once() {
+  Ctx { profile: "dev" }.get_provider();
+  assert_eq!(SMTP_CHECKS.get(), 1);
+}
+
+#[test]
+#[should_panic(expected = "no provider of `Cache`, needed by `Service`, is active")]
+fn test_no_active_candidate() {
+  Ctx { profile: "staging" }.get_provider();
+}
+
+#[test]
+#[should_panic(expected = "2 providers of `Mailer` are active")]
+fn test_several_active_candidates() {
+  Ctx { profile: "qa" }.get_provider();
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::{
+  atomic::{AtomicUsize, Ordering},
+  Arc,
+};
+
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Url(&'static str);
+
+/// Not `Clone`: shared by every dependent.
+#[autowired(ctx = Ctx)]
+struct Pool {
+  url: Url,
+  #[inject(AtomicUsize::new(0))]
+  connections: AtomicUsize,
+}
+
+#[autowired(ctx = Ctx)]
+struct UserRepo {
+  pool: Arc<Pool>,
+}
+
+struct OrderRepo(Arc<Pool>);
+
+#[autowired(ctx = Ctx)]
+fn order_repo(pool: Arc<Pool>) -> OrderRepo {
+  pool.connections.fetch_add(1, Ordering::SeqCst);
+  OrderRepo(pool)
+}
+
+#[derive(Context)]
+struct Ctx {
+  url: Url,
+}
+
+#[test]
+fn test_shared() {
+  let provider = Ctx { url: Url("postgres://") }.get_provider();
+  let pool = provider.provide_arc::<Pool>();
+  let users = provider.provide_arc::<UserRepo>();
+
+  assert_eq!(pool.url.0, "postgres://");
+  assert!(Arc::ptr_eq(&pool, &users.pool));
+  assert!(Arc::ptr_eq(&pool, &provider.provide_arc::<OrderRepo>().0));
+  assert_eq!(pool.connections.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "inventory")]
+
+use std::collections::HashMap;
+
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Settings {
+  limits: HashMap<&'static str, usize>,
+}
+
+#[autowired(ctx = Ctx)]
+fn settings() -> Settings {
+  Settings {
+    limits: HashMap::from([("uploads", 10)]),
+  }
+}
+
+#[derive(Context)]
+struct Ctx {
+  name: &'static str,
+}
+
+#[test]
+fn test_get() {
+  let provider = Ctx { name: "app" }.get_provider();
+
+  let settings = provider.get::<Settings>();
+  assert_eq!(settings.limits["uploads"], 10);
+  assert!(std::ptr::eq(settings, provider.get::<Settings>()));
+  assert_eq!(*provider.get::<&str>(), "app");
+  assert_eq!(provider.provide::<Settings>().limits, settings.limits);
+}
@@ -0,0 +1,78 @@
+#![cfg(all(feature = "axum", feature = "inventory"))]
+
+use autowired::{
+  autowired,
+  axum::{Inject, ProviderState},
+  Context, Provider,
+};
+use axum::{body::Body, extract::FromRef, http::Request, routing::get, Router};
+use tower::ServiceExt;
+
+#[derive(Clone)]
+struct Greeting(&'static str);
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct UserService {
+  greeting: Greeting,
+}
+
+impl UserService {
+  fn greet(&self, name: &str) -> String {
+    format!("{}, {}", self.greeting.0, name)
+  }
+}
+
+#[derive(Context)]
+struct Ctx {
+  greeting: Greeting,
+}
+
+async fn hello(Inject(users): Inject<UserService>) -> String {
+  users.greet("alice")
+}
+
+async fn body(router: Router) -> String {
+  let response = router
+    .oneshot(Request::get("/").body(Body::empty()).unwrap())
+    .await
+    .unwrap();
+  let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+  String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_provider_state() {
+  let provider = Ctx { greeting: Greeting("hello") }.get_provider();
+  let router = Router::new().route("/", get(hello)).with_state(provider);
+
+  assert_eq!(body(router).await, "hello, alice");
+}
+
+#[derive(Clone)]
+struct AppState {
+  provider: Provider<Ctx>,
+  version: u32,
+}
+
+impl FromRef<AppState> for Provider<Ctx> {
+  fn from_ref(state: &AppState) -> Self {
+    state.provider.clone()
+  }
+}
+
+impl ProviderState for AppState {
+  type Context = Ctx;
+}
+
+#[tokio::test]
+async fn test_app_state() {
+  let state = AppState {
+    provider: Ctx { greeting: Greeting("hi") }.get_provider(),
+    version: 1,
+  };
+  assert_eq!(state.version, 1);
+  let router = Router::new().route("/", get(hello)).with_state(state);
+
+  assert_eq!(body(router).await, "hi, alice");
+}
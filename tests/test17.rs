@@ -0,0 +1,74 @@
+#![cfg(all(feature = "actix", feature = "inventory"))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use actix_web::{test, web, App};
+use autowired::{
+  actix::{AppExt, Inject, RequestScope},
+  autowired, Context,
+};
+
+static USER_INITS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone)]
+struct Pool(&'static str);
+
+#[derive(Clone)]
+struct UserId(String);
+
+#[derive(Clone)]
+struct CurrentUser(String);
+
+#[autowired(ctx = Ctx, scoped)]
+fn current_user(id: UserId) -> CurrentUser {
+  USER_INITS.fetch_add(1, Ordering::SeqCst);
+  CurrentUser(id.0)
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx, scoped)]
+struct Transaction {
+  pool: Pool,
+  user: CurrentUser,
+}
+
+#[derive(Context)]
+#[scoped(UserId)]
+struct Ctx {
+  pool: Pool,
+}
+
+async fn whoami(Inject(user): Inject<CurrentUser>, Inject(tx): Inject<Transaction>) -> String {
+  format!("{} as {} on {}", user.0, tx.user.0, tx.pool.0)
+}
+
+#[actix_web::test]
+async fn test_request_scope() {
+  let provider = Ctx { pool: Pool("postgres") }.get_provider();
+  let scope = RequestScope::<Ctx>::new(|req, deps| {
+    let id = req.headers().get("x-user").and_then(|v| v.to_str().ok()).unwrap_or("guest");
+    deps.insert(UserId(id.into()));
+  });
+  let app = test::init_service(App::new().provider(provider).wrap(scope).route("/", web::get().to(whoami))).await;
+
+  for user in ["alice", "bob"] {
+    let req = test::TestRequest::get().uri("/").insert_header(("x-user", user)).to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, format!("{} as {} on postgres", user, user));
+  }
+  assert_eq!(USER_INITS.load(Ordering::SeqCst), 2);
+}
+
+#[actix_web::test]
+async fn test_unscoped() {
+  let provider = Ctx { pool: Pool("postgres") }.get_provider();
+  let app = test::init_service(
+    App::new()
+      .provider(provider)
+      .route("/", web::get().to(|Inject(pool): Inject<Pool>| async move { pool.0 })),
+  )
+  .await;
+
+  let body = test::call_and_read_body(&app, test::TestRequest::get().uri("/").to_request()).await;
+  assert_eq!(body, "postgres");
+}
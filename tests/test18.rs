@@ -0,0 +1,40 @@
+#![cfg(all(feature = "tokio", feature = "inventory"))]
+
+use autowired::{autowired, current, try_current, Context, CurrentError};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Db(&'static str);
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Jobs {
+  db: Db,
+}
+
+#[derive(Context)]
+struct Ctx {
+  db: Db,
+}
+
+async fn run_job() -> &'static str {
+  tokio::task::yield_now().await;
+  current::<Jobs>().db.0
+}
+
+#[tokio::test]
+async fn test_scope() {
+  let provider = Ctx { db: Db("postgres") }.get_provider();
+
+  assert_eq!(provider.scope(run_job()).await, "postgres");
+  assert_eq!(
+    provider.scope(async { try_current::<String>() }).await,
+    Err(CurrentError::NotProvided("alloc::string::String"))
+  );
+  assert_eq!(try_current::<Db>(), Err(CurrentError::NoScope));
+}
+
+#[test]
+#[should_panic(expected = "`autowired::current` called outside of `Provider::scope`")]
+fn test_no_scope() {
+  current::<Db>();
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use autowired::{autowired, Context, Factory, Lazy};
+
+static HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone)]
+struct Queue(&'static str);
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Scheduler {
+  runner: Lazy<Runner>,
+}
+
+/// Depends on `Scheduler`, which depends on it lazily.
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Runner {
+  scheduler: Scheduler,
+  handlers: Factory<Handler>,
+}
+
+struct Handler {
+  id: usize,
+  queue: Queue,
+}
+
+#[autowired(ctx = Ctx)]
+fn handler(queue: Queue) -> Handler {
+  Handler {
+    id: HANDLERS.fetch_add(1, Ordering::SeqCst),
+    queue,
+  }
+}
+
+#[derive(Context)]
+struct Ctx {
+  queue: Queue,
+}
+
+#[test]
+fn test_deferred() {
+  let provider = Ctx { queue: Queue("jobs") }.get_provider();
+  let runner = provider.provide::<Runner>();
+  let scheduler = provider.provide::<Scheduler>();
+
+  assert_eq!(scheduler.runner.get().scheduler.runner.get().handlers.get().queue.0, "jobs");
+
+  let first = runner.handlers.get().id;
+  assert_eq!(runner.handlers.get().id, first + 1);
+}
+
+#[derive(Clone)]
+struct Depth(usize);
+
+#[autowired(ctx = Ctx)]
+fn depth() -> Depth {
+  Depth(3)
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Dashboard {
+  depth: Depth,
+}
+
+#[autowired(ctx = Ctx)]
+struct Reporter {
+  depth: Lazy<Depth>,
+}
+
+#[test]
+fn test_lazy_of_overridden_dependency() {
+  let provider = Ctx { queue: Queue("jobs") }
+    .provider_builder()
+    .override_with(Dashboard { depth: Depth(0) })
+    .build();
+
+  assert_eq!(provider.get::<Dashboard>().depth.0, 0);
+  assert_eq!(provider.get::<Reporter>().depth.get().0, 3);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "inventory")]
+
 use ::autowired::*;
 use std::sync::Arc;
 
@@ -0,0 +1,60 @@
+use autowired::{autowired_module, Context};
+
+autowired_module!(
+  pub mod tenants {
+    use autowired::autowired;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Db(pub &'static str);
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TenantId(pub u32);
+
+    #[derive(Debug, PartialEq)]
+    pub struct TenantService {
+      pub db: Db,
+      pub region: &'static str,
+      pub tenant_id: TenantId,
+    }
+
+    #[autowired(ctx = super::Ctx, assisted)]
+    pub fn tenant_service(db: Db, #[inject("eu")] region: &'static str, #[runtime] tenant_id: TenantId) -> TenantService {
+      TenantService { db, region, tenant_id }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Report {
+      pub db: Db,
+      pub year: u16,
+    }
+
+    #[autowired(ctx = super::Ctx, assisted)]
+    impl Report {
+      pub fn new(#[runtime] year: u16, db: Db) -> Self {
+        Self { db, year }
+      }
+    }
+  }
+);
+
+use tenants::{Db, ReportFactory, TenantId, TenantServiceFactory};
+
+#[derive(Context)]
+#[modules(tenants::MODULE)]
+struct Ctx {
+  db: Db,
+}
+
+#[test]
+fn test_assisted() {
+  let provider = Ctx { db: Db("postgres") }.get_provider();
+
+  let tenants = provider.provide::<TenantServiceFactory>();
+  let service = tenants.create(TenantId(7));
+  assert_eq!(service.db, Db("postgres"));
+  assert_eq!(service.region, "eu");
+  assert_eq!(service.tenant_id, TenantId(7));
+
+  let report = provider.get::<ReportFactory>().create(2024);
+  assert_eq!(report.year, 2024);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Db(&'static str);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Plugins(Vec<&'static str>);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Registry {
+  db: Db,
+  plugins: Plugins,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Stats(usize);
+
+#[derive(Debug, Clone)]
+#[autowired(ctx = Ctx)]
+struct Admin {
+  registry: Registry,
+}
+
+#[derive(Clone)]
+struct Session(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn session() -> Session {
+  Session("registered")
+}
+
+#[derive(Context)]
+#[bound(Registry)]
+struct Ctx {
+  db: Db,
+}
+
+fn builder(ctx: &Ctx) -> autowired::ProviderBuilder<'_, Ctx> {
+  ctx
+    .provider_builder()
+    .bind(Plugins(vec!["audit", "billing"]))
+    .bind_fn::<Registry, (Db, Plugins), _>(|deps| Registry {
+      db: deps.get(),
+      plugins: deps.get(),
+    })
+}
+
+#[test]
+fn test_bind() {
+  let ctx = Ctx { db: Db("postgres") };
+  let provider = builder(&ctx).build();
+  let admin = provider.provide::<Admin>();
+
+  assert_eq!(admin.registry.db, Db("postgres"));
+  assert_eq!(admin.registry.plugins, Plugins(vec!["audit", "billing"]));
+  assert_eq!(provider.get::<Session>().0, "registered");
+}
+
+#[tokio::test]
+async fn test_bind_async() {
+  let ctx = Ctx { db: Db("postgres") };
+  let provider = builder(&ctx)
+    .bind_async::<Stats, (Registry,), _, _>(|deps| {
+      let registry = deps.get::<Registry>();
+      async move { Stats(registry.plugins.0.len()) }
+    })
+    .async_build()
+    .await;
+
+  assert_eq!(provider.provide::<Stats>(), Stats(2));
+}
+
+#[test]
+#[should_panic(expected = "2 providers of `Session` are active")]
+fn test_bind_registered() {
+  Ctx { db: Db("postgres") }
+    .provider_builder()
+    .bind(Session("bound"))
+    .build();
+}
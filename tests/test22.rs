@@ -0,0 +1,68 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::Arc;
+
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Url(&'static str);
+
+#[derive(Clone)]
+struct Pool;
+
+#[autowired(ctx = Ctx)]
+fn pool(url: Url) -> Pool {
+  panic!("connects to {}", url.0)
+}
+
+struct Clock;
+
+#[derive(Clone)]
+struct Repo;
+
+#[autowired(ctx = Ctx)]
+fn repo(_pool: Pool, _clock: Arc<Clock>) -> Repo {
+  Repo
+}
+
+#[derive(Clone)]
+struct Cache;
+
+#[autowired(ctx = Ctx, profile = "prod")]
+fn redis() -> Cache {
+  panic!("connects to redis")
+}
+
+#[derive(Clone)]
+struct UserId;
+
+#[derive(Clone)]
+struct User;
+
+#[autowired(ctx = Ctx, scoped)]
+fn user(_id: UserId) -> User {
+  User
+}
+
+#[derive(Context)]
+#[conditional(Cache)]
+#[scoped(UserId)]
+struct Ctx {
+  url: Url,
+  clock: Arc<Clock>,
+}
+
+#[test]
+fn test_validate() {
+  let report = Ctx {
+    url: Url("postgres://"),
+    clock: Arc::new(Clock),
+  }
+  .validate()
+  .unwrap();
+
+  let names = |ps: &[autowired::ProviderInfo]| ps.iter().map(|p| p.name).collect::<Vec<_>>();
+  assert_eq!(names(&report.providers), ["Pool", "Repo"]);
+  assert_eq!(names(&report.scoped), ["User"]);
+  assert_eq!(names(&report.inactive), ["Cache"]);
+}
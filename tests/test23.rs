@@ -0,0 +1,66 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::Arc;
+
+use autowired::{autowired, Context, Lazy, ValidationError};
+
+#[derive(Clone)]
+struct A;
+
+#[autowired(ctx = Ctx)]
+fn a(_b: Arc<B>) -> A {
+  A
+}
+
+#[derive(Clone)]
+struct B;
+
+#[autowired(ctx = Ctx)]
+fn b(_a: Arc<A>) -> B {
+  B
+}
+
+#[derive(Clone)]
+struct Mailer;
+
+#[derive(Clone)]
+struct Log;
+
+#[derive(Clone)]
+struct Notifier;
+
+#[autowired(ctx = Ctx)]
+fn notifier(_mailer: Mailer, _log: Lazy<Log>) -> Notifier {
+  Notifier
+}
+
+#[derive(Context)]
+#[conditional(Mailer, Log)]
+struct Ctx {}
+
+#[test]
+fn test_validate_errors() {
+  let errors = Ctx {}.validate().unwrap_err();
+
+  assert_eq!(
+    errors,
+    [
+      ValidationError::Missing {
+        name: "Mailer",
+        needed_by: "Notifier"
+      },
+      ValidationError::Missing {
+        name: "Log",
+        needed_by: "Notifier"
+      },
+      ValidationError::Cycle { cycle: vec!["A", "B"] },
+    ]
+  );
+  assert_eq!(errors[2].to_string(), "dependency cycle: A -> B -> A");
+}
+
+#[test]
+#[should_panic(expected = "dependency cycle: A -> B -> A")]
+fn test_cycle() {
+  Ctx {}.get_provider();
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context, Lazy};
+
+#[derive(Clone)]
+struct Url(&'static str);
+
+#[derive(Clone)]
+struct Pool(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn pool(url: Url) -> Pool {
+  Pool(url.0)
+}
+
+#[derive(Clone)]
+struct Smtp;
+
+#[autowired(ctx = Ctx)]
+fn smtp() -> Smtp {
+  Smtp
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Mailer {
+  smtp: Smtp,
+}
+
+#[autowired(ctx = Ctx)]
+struct App {
+  pool: Pool,
+  mailer: Lazy<Mailer>,
+}
+
+#[derive(Clone)]
+struct Metrics;
+
+#[autowired(ctx = Ctx)]
+fn metrics(_pool: Pool) -> Metrics {
+  panic!("metrics are unused")
+}
+
+#[derive(Clone)]
+struct Migrate(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn migrate(pool: Pool) -> Migrate {
+  Migrate(pool.0)
+}
+
+/// Depends on the `Migrate` root, but is not a root itself.
+#[derive(Clone)]
+struct Legacy;
+
+#[autowired(ctx = Ctx)]
+fn legacy(_migrate: Migrate) -> Legacy {
+  panic!("legacy is unused")
+}
+
+#[derive(Context)]
+#[roots(App, Migrate)]
+#[skip_unused]
+struct Ctx {
+  url: Url,
+}
+
+#[test]
+fn test_validate_unused() {
+  let report = Ctx { url: Url("postgres://") }.validate().unwrap();
+
+  let names = report.unused.iter().map(|p| p.name).collect::<Vec<_>>();
+  assert_eq!(names, ["Legacy", "Metrics"]);
+}
+
+#[test]
+fn test_skip_unused() {
+  let provider = Ctx { url: Url("postgres://") }.get_provider();
+
+  let app = provider.get::<App>();
+  assert_eq!(app.pool.0, "postgres://");
+  let _ = app.mailer.get().smtp;
+  assert_eq!(provider.get::<Migrate>().0, "postgres://");
+}
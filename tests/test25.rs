@@ -0,0 +1,66 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Url(&'static str);
+
+#[derive(Clone)]
+struct Pool(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn pool(url: Url) -> Pool {
+  Pool(url.0)
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct MigrateCommand {
+  pool: Pool,
+}
+
+#[derive(Clone)]
+struct Listener;
+
+#[autowired(ctx = Ctx)]
+fn listener() -> Listener {
+  panic!("binds a port")
+}
+
+#[derive(Clone)]
+struct ServeCommand;
+
+#[autowired(ctx = Ctx)]
+fn serve_command(_pool: Pool, _listener: Listener) -> ServeCommand {
+  ServeCommand
+}
+
+#[derive(Clone)]
+struct Schema(u32);
+
+#[autowired(ctx = Ctx)]
+async fn schema(pool: Pool) -> Schema {
+  Schema(pool.0.len() as u32)
+}
+
+#[derive(Context)]
+struct Ctx {
+  url: Url,
+}
+
+#[test]
+fn test_provider_for() {
+  let provider = Ctx { url: Url("postgres://") }.get_provider_for::<(MigrateCommand,)>();
+
+  assert_eq!(provider.provide::<MigrateCommand>().pool.0, "postgres://");
+}
+
+#[tokio::test]
+async fn test_async_provider_for() {
+  let provider = Ctx { url: Url("postgres://") }
+    .get_async_provider_for::<(MigrateCommand, Schema)>()
+    .await;
+
+  assert_eq!(provider.provide::<MigrateCommand>().pool.0, "postgres://");
+  assert_eq!(provider.provide::<Schema>().0, 11);
+}
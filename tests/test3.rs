@@ -0,0 +1,61 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use autowired::{autowired, Context};
+
+static POOL_INITS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Pool(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn pool() -> Pool {
+  POOL_INITS.fetch_add(1, Ordering::SeqCst);
+  Pool("postgres")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Db(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn db(pool: Pool) -> Db {
+  Db(pool.0)
+}
+
+#[derive(Debug, Clone)]
+#[autowired(ctx = Ctx)]
+struct Repo {
+  db: Db,
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+#[test]
+fn test_override() {
+  let p = Ctx {}
+    .provider_builder()
+    .override_with(Db("fake"))
+    .build();
+
+  assert_eq!(p.provide::<Repo>().db, Db("fake"));
+  assert_eq!(POOL_INITS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_override_turbofish() {
+  let p = Ctx {}
+    .provider_builder()
+    .override_with::<Db>(Db("fake"))
+    .build();
+
+  assert_eq!(p.provide::<Repo>().db, Db("fake"));
+  assert_eq!(POOL_INITS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+#[should_panic(expected = "does not provide it")]
+fn test_override_unprovided() {
+  Ctx {}.provider_builder().override_with::<u32>(0).build();
+}
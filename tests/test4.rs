@@ -0,0 +1,57 @@
+use autowired::{autowired_module, Context};
+
+autowired_module!(
+  pub mod storage {
+    use autowired::autowired;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Url(pub &'static str);
+
+    #[autowired(ctx = super::Ctx)]
+    impl Url {
+      fn new() -> Self {
+        Url("postgres://localhost")
+      }
+    }
+
+    #[derive(Debug, Clone)]
+    #[autowired(ctx = super::Ctx)]
+    pub struct Db {
+      pub url: Url,
+    }
+  }
+);
+
+autowired_module!(
+  pub mod jobs {
+    use autowired::autowired;
+
+    use super::storage::Db;
+
+    #[derive(Debug, Clone)]
+    pub struct Worker(pub Db);
+
+    #[autowired(ctx = super::Ctx)]
+    async fn worker(db: Db) -> Worker {
+      Worker(db)
+    }
+  }
+);
+
+#[derive(Context)]
+#[modules(storage::MODULE, jobs::MODULE)]
+struct Ctx {}
+
+#[test]
+fn test_modules() {
+  let p = Ctx {}.get_provider();
+
+  assert_eq!(p.provide::<storage::Db>().url, storage::Url("postgres://localhost"));
+}
+
+#[tokio::test]
+async fn test_async_modules() {
+  let p = Ctx {}.get_async_provider().await;
+
+  assert_eq!(p.provide::<jobs::Worker>().0.url, storage::Url("postgres://localhost"));
+}
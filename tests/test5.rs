@@ -0,0 +1,47 @@
+#![cfg(feature = "inventory")]
+
+use std::sync::Mutex;
+
+use autowired::{autowired, Context};
+
+static LOG: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+#[derive(Clone)]
+struct Mid;
+
+#[autowired(ctx = Ctx)]
+fn mid() -> Mid {
+  LOG.lock().unwrap().push("Mid");
+  Mid
+}
+
+#[derive(Clone)]
+struct Zeta;
+
+#[autowired(ctx = Ctx)]
+fn zeta() -> Zeta {
+  LOG.lock().unwrap().push("Zeta");
+  Zeta
+}
+
+#[derive(Clone)]
+struct Alpha;
+
+#[autowired(ctx = Ctx)]
+fn alpha(_zeta: Zeta) -> Alpha {
+  LOG.lock().unwrap().push("Alpha");
+  Alpha
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+#[test]
+fn test_build_order() {
+  for _ in 0..10 {
+    LOG.lock().unwrap().clear();
+    Ctx {}.get_provider();
+
+    assert_eq!(*LOG.lock().unwrap(), ["Zeta", "Alpha", "Mid"]);
+  }
+}
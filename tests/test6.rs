@@ -0,0 +1,41 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context};
+
+mod config {
+  #[derive(Debug, Clone)]
+  pub struct Cfg(pub &'static str);
+}
+
+use config::Cfg;
+
+#[autowired(ctx = Ctx)]
+fn cfg() -> crate::config::Cfg {
+  Cfg("cfg")
+}
+
+#[derive(Debug, Clone)]
+struct Pool(Cfg);
+
+type Conn = Pool;
+
+#[autowired(ctx = Ctx)]
+fn pool(cfg: Cfg) -> crate::Pool {
+  Pool(cfg)
+}
+
+#[derive(Debug, Clone)]
+#[autowired(ctx = Ctx)]
+struct Svc {
+  conn: Conn,
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+#[test]
+fn test_type_id_children() {
+  let p = Ctx {}.get_provider();
+
+  assert_eq!(p.provide::<Svc>().conn.0 .0, "cfg");
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "inventory")]
+
+use std::marker::PhantomData;
+
+use autowired::{autowired, Context};
+
+trait Entity: Clone + Send + Sync {
+  const TABLE: &'static str;
+}
+
+#[derive(Clone)]
+struct User;
+
+impl Entity for User {
+  const TABLE: &'static str = "users";
+}
+
+#[derive(Clone)]
+struct Order;
+
+impl Entity for Order {
+  const TABLE: &'static str = "orders";
+}
+
+#[derive(Clone)]
+struct Db(&'static str);
+
+#[autowired(ctx = Ctx)]
+fn db() -> Db {
+  Db("postgres")
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Repo<T: Entity> {
+  db: Db,
+  #[inject(PhantomData)]
+  _entity: PhantomData<T>,
+}
+
+impl<T: Entity> Repo<T> {
+  fn table(&self) -> String {
+    format!("{}/{}", self.db.0, T::TABLE)
+  }
+}
+
+#[derive(Clone)]
+struct Cache<T: Entity>(Repo<T>);
+
+#[autowired(ctx = Ctx)]
+fn cache<T: Entity>(repo: Repo<T>) -> Cache<T> {
+  Cache(repo)
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct UserService {
+  users: Cache<User>,
+}
+
+#[derive(Context)]
+#[instantiate(Repo<Order>)]
+struct Ctx {}
+
+#[test]
+fn test_generic_providers() {
+  let p = Ctx {}.get_provider();
+
+  assert_eq!(p.provide::<UserService>().users.0.table(), "postgres/users");
+  assert_eq!(p.provide::<Repo<User>>().table(), "postgres/users");
+  assert_eq!(p.provide::<Repo<Order>>().table(), "postgres/orders");
+}
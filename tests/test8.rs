@@ -0,0 +1,72 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+  url: &'static str,
+}
+
+#[autowired(ctx = Ctx)]
+impl Config {
+  fn new() -> Self {
+    Self { url: "postgres" }
+  }
+
+  #[allow(dead_code)]
+  fn url(&self) -> &'static str {
+    self.url
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Storage {
+  config: Config,
+  prefix: &'static str,
+}
+
+#[autowired(ctx = Ctx)]
+impl Storage {
+  #[allow(dead_code)]
+  fn new(config: Config) -> Self {
+    Self {
+      config,
+      prefix: "",
+    }
+  }
+
+  #[autowired]
+  fn with_prefix(config: Config, #[inject("tmp")] prefix: &'static str) -> Storage {
+    Self { config, prefix }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Pool(Storage);
+
+#[autowired(ctx = Ctx)]
+impl Pool {
+  #[autowired]
+  async fn connect(storage: Storage) -> Self {
+    Self(storage)
+  }
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+#[test]
+fn test_impl_providers() {
+  let p = Ctx {}.get_provider();
+
+  let storage = p.provide::<Storage>();
+  assert_eq!(storage.config, Config { url: "postgres" });
+  assert_eq!(storage.prefix, "tmp");
+}
+
+#[tokio::test]
+async fn test_async_impl_providers() {
+  let p = Ctx {}.get_async_provider().await;
+
+  assert_eq!(p.provide::<Pool>().0.prefix, "tmp");
+}
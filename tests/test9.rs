@@ -0,0 +1,52 @@
+#![cfg(feature = "inventory")]
+
+use autowired::{autowired, Context};
+
+#[derive(Debug, Clone, PartialEq)]
+#[autowired(ctx = Ctx)]
+#[inject(env!("CARGO_PKG_NAME"))]
+struct ApiKey(String);
+
+#[derive(Debug, Clone, PartialEq)]
+#[autowired(ctx = Ctx)]
+struct Url(#[inject("postgres://localhost")] &'static str);
+
+#[derive(Debug, Clone, PartialEq)]
+#[autowired(ctx = Ctx, variant = Postgres)]
+enum Db {
+  #[allow(dead_code)]
+  Memory,
+  Postgres { url: Url, key: ApiKey },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cache {
+  Memory,
+  #[allow(dead_code)]
+  Redis(Url),
+}
+
+#[autowired(ctx = Ctx)]
+impl Cache {
+  fn from_config(_key: ApiKey) -> Self {
+    Self::Memory
+  }
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+#[test]
+fn test_enums_and_newtypes() {
+  let p = Ctx {}.get_provider();
+
+  assert_eq!(p.provide::<ApiKey>(), ApiKey("autowired".to_string()));
+  assert_eq!(
+    p.provide::<Db>(),
+    Db::Postgres {
+      url: Url("postgres://localhost"),
+      key: ApiKey("autowired".to_string()),
+    }
+  );
+  assert_eq!(p.provide::<Cache>(), Cache::Memory);
+}
@@ -0,0 +1,22 @@
+use autowired::{autowired, Context, Factory};
+
+#[derive(Clone)]
+struct Pool;
+
+#[autowired(ctx = Ctx)]
+async fn pool() -> Pool {
+  Pool
+}
+
+#[derive(Clone)]
+struct Jobs(Pool);
+
+#[autowired(ctx = Ctx)]
+async fn jobs(pools: Factory<Pool>) -> Jobs {
+  Jobs(pools.get())
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use autowired::{autowired, Context};
+
+struct Storage;
+
+impl Storage {
+  #[autowired(ctx = Ctx)]
+  fn new() -> Self {
+    Storage
+  }
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
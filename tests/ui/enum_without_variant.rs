@@ -0,0 +1,20 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+enum Cache {
+  Memory,
+  Redis,
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx, variant = Postgres)]
+enum Db {
+  Memory,
+  Sqlite,
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
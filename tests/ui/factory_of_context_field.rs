@@ -0,0 +1,17 @@
+use autowired::{autowired, Context, Factory};
+
+#[derive(Clone)]
+struct Queue;
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Runner {
+  queues: Factory<Queue>,
+}
+
+#[derive(Context)]
+struct Ctx {
+  queue: Queue,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use autowired::{autowired, Context, Factory, Lazy};
+
+#[derive(Clone)]
+struct Db;
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Repo {
+  db: Lazy<Db>,
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Service {
+  dbs: Factory<Db>,
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Db;
+
+#[derive(Clone)]
+struct Cache;
+
+#[autowired(ctx = Ctx)]
+fn cache() -> Cache {
+  Cache
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Repo {
+  db: Db,
+  cache: Cache,
+}
+
+#[derive(Clone)]
+struct Service;
+
+#[autowired(ctx = Ctx)]
+fn service(cache: Cache, db: Db) -> Service {
+  let _ = (cache, db);
+  Service
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
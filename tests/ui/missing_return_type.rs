@@ -0,0 +1,9 @@
+use autowired::{autowired, Context};
+
+#[autowired(ctx = Ctx)]
+fn setup() {}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
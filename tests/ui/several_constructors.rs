@@ -0,0 +1,20 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Storage(usize);
+
+#[autowired(ctx = Ctx)]
+impl Storage {
+  fn new() -> Self {
+    Storage(0)
+  }
+
+  fn with_capacity() -> Storage {
+    Storage(16)
+  }
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Pool;
+
+#[autowired(ctx = Ctx)]
+async fn pool() -> Pool {
+  Pool
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Repo {
+  pool: Pool,
+}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}
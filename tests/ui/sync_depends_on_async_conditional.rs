@@ -0,0 +1,21 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Cache;
+
+#[autowired(ctx = Ctx, profile = "dev")]
+async fn mem_cache() -> Cache {
+  Cache
+}
+
+#[derive(Clone)]
+#[autowired(ctx = Ctx)]
+struct Svc {
+  cache: Cache,
+}
+
+#[derive(Context)]
+#[conditional(async Cache)]
+struct Ctx {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use autowired::{autowired, Context};
+
+#[derive(Clone)]
+struct Cache;
+
+#[autowired(ctx = Ctx, profile = "dev")]
+async fn mem_cache() -> Cache {
+  Cache
+}
+
+#[derive(Context)]
+#[conditional(Cache)]
+struct Ctx {}
+
+fn main() {}
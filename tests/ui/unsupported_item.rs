@@ -0,0 +1,9 @@
+use autowired::{autowired, Context};
+
+#[autowired(ctx = Ctx)]
+trait Repo {}
+
+#[derive(Context)]
+struct Ctx {}
+
+fn main() {}